@@ -1,10 +1,14 @@
 use br_data::context::Context;
-use crossterm::event::{KeyCode, KeyModifiers};
+use crossterm::{
+    event::{KeyCode, KeyModifiers},
+    execute,
+    style::Print,
+};
 
 use crate::{
     history::handle_history,
     tabcomp::handle_tab,
-    util::{move_cursor, print_cmd_buf, restore_backup},
+    util::{move_cursor, print_cmd_buf, print_error, restore_backup},
 };
 
 pub fn handle_key(ctx: &mut Context) -> bool {
@@ -14,6 +18,12 @@ pub fn handle_key(ctx: &mut Context) -> bool {
         // History
         KeyCode::Up | KeyCode::Down => handle_history(ctx),
 
+        // Cancel the current line
+        KeyCode::Char('c') if ctx.cli.current_key.modifiers == KeyModifiers::CONTROL => {
+            handle_interrupt(ctx);
+            return false;
+        },
+
         // Command buffer manipulation
         KeyCode::Char(_) => handle_char(ctx),
         KeyCode::Backspace => handle_backspace(ctx),
@@ -52,15 +62,20 @@ fn handle_char(ctx: &mut Context) {
 
     let pos = (ctx.cli.cursor_pos.0 as usize) - ctx.cli.prompt_len();
 
-    if pressed_key == 'c' && ctx.cli.current_key.modifiers == KeyModifiers::CONTROL {
-        ctx.cli.command_buffer = String::new();
-        print_cmd_buf(ctx, -(pos as i16));
+    ctx.cli.command_buffer.insert(pos, pressed_key);
+    print_cmd_buf(ctx, 1);
+}
 
-        return;
+// Leave the line on screen with a '^C' marker and start a new
+// prompt, same as other shells
+fn handle_interrupt(ctx: &mut Context) {
+    move_eol(ctx);
+    if let Err(why) = execute!(&ctx.writer, Print("^C")) {
+        print_error(ctx, format!("Unable to print interrupt! {}", why));
     }
 
-    ctx.cli.command_buffer.insert(pos, pressed_key);
-    print_cmd_buf(ctx, 1);
+    ctx.cli.command_buffer = String::new();
+    ctx.last_status = 130;
 }
 
 fn move_sol(ctx: &mut Context) {
@@ -1,12 +1,16 @@
-use br_command::load_builtins;
-use br_data::{context::Context, options::Options};
-use br_executer::execute;
+use std::io::stdin;
+
+use br_data::{command::ExecOutcome, context::Context, hook::HookEvent, job::JobState, options::Options};
+#[cfg(unix)]
+use br_executer::run_traps;
+use br_executer::{execute, run_exit_handlers, run_hooks};
 use br_parser::parse_command;
-use br_script::load_rc;
+use br_script::{init_context, load_rc};
 use crossterm::{
     cursor::position,
     event::{read, Event},
     terminal::{disable_raw_mode, enable_raw_mode},
+    tty::IsTty,
     Result,
 };
 
@@ -20,33 +24,55 @@ mod key;
 mod tabcomp;
 mod util;
 
-pub fn run_term(opts: Options) -> Result<()> {
-    // Set dummy handler so that ctrl-c doesn't terminate
-    // cli when running commands as raw mode is disabled.
-    // Print line so that the last output line doesn't get
-    // cleared on clear_error()
+pub fn stdin_is_tty() -> bool {
+    stdin().is_tty()
+}
+
+pub fn run_term(opts: Options) -> Result<i32> {
+    let mut ctx = init_context();
+
+    #[cfg(unix)]
+    {
+        // Catch ctrl-c so that it doesn't terminate the cli
+        // when running commands as raw mode is disabled
+        br_data::signal::init_signals(true);
+        ctx.job_control = br_data::job::init_job_control();
+    }
+
+    #[cfg(windows)]
     ctrlc::set_handler(move || {
         println!(" ");
     })
     .expect("Unable to setup ctrl-c handler");
 
-    let mut ctx = Context::default();
-    load_builtins(&mut ctx);
-
     if !opts.norc {
-        load_rc(&mut ctx);
+        if let ExecOutcome::Exit(exit_code) = load_rc(&mut ctx) {
+            run_exit_handlers(&mut ctx);
+            return Ok(exit_code);
+        }
     }
 
     if let Err(why) = history::init_history() {
         println!("Unable to initialise history file! {}", why);
     }
 
-    if let Err(why) = enable_raw_mode() {
-        panic!("Unable to enable raw mode! {}", why);
-    }
-
-    loop {
+    let mut warned_stopped = false;
+    let exit_code = loop {
         ctx.cli.command_buffer = String::new();
+
+        #[cfg(unix)]
+        report_jobs(&mut ctx);
+
+        run_hooks(&mut ctx, HookEvent::Precmd, &[]);
+
+        // Raw mode is only enabled while reading input so that
+        // commands and hooks function normally
+        if let Err(why) = enable_raw_mode() {
+            panic!("Unable to enable raw mode! {}", why);
+        }
+
+        // Show values set by other sessions since the last command
+        ctx.sync_universal();
         format_prompt(&mut ctx);
         print_prompt(&mut ctx);
 
@@ -92,31 +118,66 @@ pub fn run_term(opts: Options) -> Result<()> {
             print_line(&mut ctx, format!("Unable to disable raw mode! {}", why));
         }
 
-        if let Err(why) = history::add_history(ctx.cli.command_buffer.clone()) {
-            print_line(&mut ctx, format!("Unable to save command to history! {}", why))
+        // Empty when the line was cancelled with ctrl-c
+        let output = if ctx.cli.command_buffer.trim().is_empty() {
+            ExecOutcome::Status(ctx.last_status)
+        } else {
+            if let Err(why) = history::add_history(ctx.cli.command_buffer.clone()) {
+                print_line(&mut ctx, format!("Unable to save command to history! {}", why))
+            };
+
+            let line = ctx.cli.command_buffer.clone();
+            run_hooks(&mut ctx, HookEvent::Preexec, &[line]);
+
+            execute(&mut ctx)
         };
 
-        let response = execute(&mut ctx);
-        if response.is_none() {
-            break;
-        }
+        #[cfg(unix)]
+        let output = match run_traps(&mut ctx) {
+            ExecOutcome::Exit(code) => ExecOutcome::Exit(code),
+            _ => output,
+        };
 
-        // Re-enable raw mode so terminal works properly
-        if let Err(why) = enable_raw_mode() {
-            panic!("Unable to re-enable raw mode! {}", why);
-        }
+        if let ExecOutcome::Exit(exit_code) = output {
+            // Warn once before leaving stopped jobs behind
+            let has_stopped = ctx.jobs.jobs.iter().any(|job| job.state() == JobState::Stopped);
+            if !has_stopped || warned_stopped {
+                break exit_code;
+            }
 
-        let exit_code = response.unwrap();
-        if exit_code != 0 {
-            // TODO: Something with error code
+            eprintln!("There are stopped jobs!");
+            warned_stopped = true;
+        } else {
+            warned_stopped = false;
         }
-    }
+    };
 
-    if let Err(why) = disable_raw_mode() {
-        panic!("Unable to disable raw mode! Run 'reset' to manually disable! {}", why);
-    }
+    run_exit_handlers(&mut ctx);
 
     println!("\nSee you later!");
 
-    Ok(())
+    Ok(exit_code)
+}
+
+// Print a notice for each background job that has finished
+// since the last prompt
+#[cfg(unix)]
+fn report_jobs(ctx: &mut Context) {
+    if let Err(why) = ctx.jobs.poll() {
+        print_error(ctx, format!("Unable to update jobs! {}", why));
+    }
+
+    let notices: Vec<String> = ctx
+        .jobs
+        .jobs
+        .iter()
+        .filter(|job| job.state() == JobState::Done)
+        .map(|job| ctx.jobs.format(job))
+        .collect();
+
+    ctx.jobs.remove_done();
+
+    for notice in notices {
+        print_line(ctx, notice);
+    }
 }
@@ -78,7 +78,7 @@ pub fn handle_tab(ctx: &mut Context) {
         restore_backup(ctx);
     } else {
         match ctx.cli.completion.list.clone().get(ctx.cli.completion.index as usize) {
-            Some(new_arg) => do_comp(&new_arg, trimmed_command, trim_size, &old_buf, after_cursor, ctx),
+            Some(new_arg) => do_comp(new_arg, trimmed_command, trim_size, &old_buf, after_cursor, ctx),
             None => {
                 print_error(ctx, "Unable to get tab completion value");
             },
@@ -1,7 +1,7 @@
 use std::{env, fmt};
 
-use br_data::context::Context;
-use br_parser::{is_valid_command, lexer::Token, parser::ParseError};
+use br_data::{context::Context, dirs};
+use br_parser::{is_valid_command, lexer::Token, parser::ParseError, OutputType};
 use crossterm::{
     cursor::{MoveLeft, MoveRight, MoveToNextLine, MoveToPreviousLine, RestorePosition, SavePosition},
     execute,
@@ -15,7 +15,7 @@ pub fn move_cursor(ctx: &mut Context, move_size: i16) {
     if let Err(why) = if move_size >= 1 {
         execute!(&ctx.writer, MoveRight(move_size as u16))
     } else if move_size <= -1 {
-        execute!(&ctx.writer, MoveLeft(move_size.abs() as u16))
+        execute!(&ctx.writer, MoveLeft(move_size.unsigned_abs()))
     } else {
         Ok(())
     } {
@@ -105,13 +105,23 @@ impl<'t> Command for PrintCmdBuf<'t> {
             return write!(writer, "{}", command_buffer);
         }
 
-        let mut lexer = Token::lexer(&command_buffer);
+        let mut lexer = Token::lexer(command_buffer);
         let mut last_token: Option<Token> = None;
         while let Some(token) = lexer.next() {
             let token_str = lexer.slice();
 
-            let colored = if last_token == None || matches!(last_token.clone(), Some(Token::Output(_))) {
-                if is_valid_command(&token_str, ctx) {
+            // Words following a redirect are file names rather
+            // than commands
+            let is_command = match &last_token {
+                None => true,
+                Some(Token::Output(out_type)) => {
+                    !matches!(out_type, OutputType::Redirect | OutputType::RedirectAppend)
+                },
+                _ => false,
+            };
+
+            let colored = if is_command {
+                if is_valid_command(token_str, ctx) {
                     token_str.dark_green()
                 } else {
                     token_str.dark_red()
@@ -150,21 +160,28 @@ pub fn format_prompt(ctx: &mut Context) {
     let mut prompt_format = ctx.get_variable("PROMPT", String::from("{WD} | "), false);
 
     if prompt_format.contains("{WD}") {
-        let mut working_dir = env::current_dir()
-            .unwrap_or_default()
+        let mut working_dir = dirs::current_dir()
             .to_str()
             .unwrap_or("[Error]")
             .to_string();
 
+        let bookmark = if ctx.get_variable("P_BOOKMARKS", true, false) {
+            ctx.bookmarks.shorten(&working_dir)
+        } else {
+            None
+        };
+
         let home_trunc = ctx.get_variable("P_HOME_TRUNC", true, false);
-        if home_trunc {
+        if let Some(bookmark) = bookmark {
+            working_dir = bookmark;
+        } else if home_trunc {
             if let Ok(home) = env::var("HOME") {
                 if working_dir.starts_with(&home) {
                     let home_trunc_char = ctx.get_variable("P_HOME_CHAR", String::from("~"), false);
                     working_dir = format!(
                         "{}{}",
                         home_trunc_char,
-                        working_dir[home.len()..working_dir.len()].to_string()
+                        &working_dir[home.len()..working_dir.len()]
                     );
                 }
             }
@@ -185,7 +202,7 @@ pub fn format_prompt(ctx: &mut Context) {
     }
 
     if prompt_format.contains("{HOST}") {
-        let host = whoami::hostname();
+        let host = whoami::fallible::hostname().unwrap_or_default();
         prompt_format = prompt_format.replace("{HOST}", &host);
     }
 
@@ -1,5 +1,5 @@
 use br_data::{
-    command::{BrBuiltin, ExecuteFn, TabCompletion, TabCompletionFn, TabCompletionType},
+    command::{BrBuiltin, CommandIo, ExecOutcome, ExecuteFn, TabCompletion, TabCompletionFn, TabCompletionType},
     context::Context,
 };
 
@@ -19,7 +19,7 @@ lazy_static! {
 #[allow(non_upper_case_globals)]
 const tc_alias_list: TabCompletionFn = |args: Vec<String>, ctx: &Context| -> Vec<String> {
     if args.len() <= 1 {
-        let cur_arg = if let Some(arg) = args.get(0) {
+        let cur_arg = if let Some(arg) = args.first() {
             arg.clone()
         } else {
             String::new()
@@ -35,88 +35,87 @@ const tc_alias_list: TabCompletionFn = |args: Vec<String>, ctx: &Context| -> Vec
 };
 
 #[allow(non_upper_case_globals)]
-const execute: ExecuteFn = |mut args: Vec<String>, ctx: &mut Context| -> i32 {
+const execute: ExecuteFn = |mut args: Vec<String>, ctx: &mut Context, io: &mut CommandIo| -> ExecOutcome {
     if args.is_empty() {
-        eprintln!("Invalid arguments! Expected 1-2, got 0");
+        io.eprintln("Invalid arguments! Expected 1-2, got 0");
 
-        return 1;
+        return ExecOutcome::Status(1);
     }
 
     let operator = args[0].clone();
     args.remove(0);
 
     match operator.as_ref() {
-        "get" => get_alias(args, ctx),
-        "set" => set_alias(args, ctx),
-        "del" => del_alias(args, ctx),
-        "list" => list_aliases(ctx),
+        "get" => get_alias(args, ctx, io),
+        "set" => set_alias(args, ctx, io),
+        "del" => del_alias(args, ctx, io),
+        "list" => list_aliases(ctx, io),
         _ => {
-            eprintln!("Invalid argument at pos 1! Expected one of 'get', 'set', 'del' or 'list'");
+            io.eprintln("Invalid argument at pos 1! Expected one of 'get', 'set', 'del' or 'list'");
 
-            1
+            ExecOutcome::Status(1)
         },
     }
 };
 
-fn get_alias(args: Vec<String>, ctx: &mut Context) -> i32 {
+fn get_alias(args: Vec<String>, ctx: &mut Context, io: &mut CommandIo) -> ExecOutcome {
     if args.is_empty() {
-        eprintln!("Invalid arguments! Expected 2, got 1");
+        io.eprintln("Invalid arguments! Expected 2, got 1");
 
-        return 1;
+        return ExecOutcome::Status(1);
     }
 
     let key = args[0].clone();
     if ctx.aliases.contains_key(&key) {
-        println!(
-            "{}",
+        io.println(
             ctx.aliases
                 .get(&key)
                 .unwrap_or(&String::from("Error occured while getting alias"))
         );
 
-        return 0;
+        return ExecOutcome::Status(0);
     }
 
-    eprintln!("Unable to find alias '{}'!", key);
+    io.eprintln(format!("Unable to find alias '{}'!", key));
 
-    1
+    ExecOutcome::Status(1)
 }
 
-fn set_alias(args: Vec<String>, ctx: &mut Context) -> i32 {
+fn set_alias(args: Vec<String>, ctx: &mut Context, io: &mut CommandIo) -> ExecOutcome {
     if args.len() != 2 {
-        eprintln!("Invalid arguments! Expected 3, got {}", args.len() + 1);
+        io.eprintln(format!("Invalid arguments! Expected 3, got {}", args.len() + 1));
 
-        return 1;
+        return ExecOutcome::Status(1);
     }
 
     let key = args[0].clone();
     let value = args[1].clone();
     ctx.aliases.insert(key, value);
 
-    0
+    ExecOutcome::Status(0)
 }
 
-fn del_alias(args: Vec<String>, ctx: &mut Context) -> i32 {
+fn del_alias(args: Vec<String>, ctx: &mut Context, io: &mut CommandIo) -> ExecOutcome {
     if args.is_empty() {
-        eprintln!("Invalid arguments! Expected 2, got 1");
+        io.eprintln("Invalid arguments! Expected 2, got 1");
 
-        return 1;
+        return ExecOutcome::Status(1);
     }
 
     let key = args[0].clone();
     if ctx.aliases.contains_key(&key) {
         ctx.aliases.remove(&key);
 
-        return 0;
+        return ExecOutcome::Status(0);
     }
 
-    eprintln!("Unable to find alias '{}'!", key);
+    io.eprintln(format!("Unable to find alias '{}'!", key));
 
-    1
+    ExecOutcome::Status(1)
 }
 
-fn list_aliases(ctx: &mut Context) -> i32 {
-    println!("{:?}", ctx.aliases.keys());
+fn list_aliases(ctx: &mut Context, io: &mut CommandIo) -> ExecOutcome {
+    io.println(format!("{:?}", ctx.aliases.keys()));
 
-    0
+    ExecOutcome::Status(0)
 }
@@ -0,0 +1,48 @@
+use br_data::{
+    command::{BrBuiltin, CommandIo, ExecOutcome, ExecuteFn, TabCompletionType},
+    context::Context,
+    job,
+};
+
+use super::jobs::tc_job_list;
+
+pub const CMD: BrBuiltin = BrBuiltin {
+    name: "bg",
+    tab_completion: TabCompletionType::Dynamic(tc_job_list),
+    execute,
+};
+
+#[allow(non_upper_case_globals)]
+const execute: ExecuteFn = |args: Vec<String>, ctx: &mut Context, io: &mut CommandIo| -> ExecOutcome {
+    let specs = if args.is_empty() { vec![None] } else { args.iter().map(|arg| Some(arg.as_str())).collect() };
+
+    let mut output = ExecOutcome::Status(0);
+    for spec in specs {
+        let id = match ctx.jobs.find(spec) {
+            Some(id) => id,
+            None => {
+                io.eprintln(format!("Unable to find job '{}'!", spec.unwrap_or("%+")));
+                output = ExecOutcome::Status(1);
+
+                continue;
+            },
+        };
+
+        let marker = ctx.jobs.marker(id);
+        let job = match ctx.jobs.get_mut(id) {
+            Some(job) => job,
+            None => continue,
+        };
+
+        if let Err(why) = job::continue_job(job) {
+            io.eprintln(format!("Unable to continue job! {}", why));
+            output = ExecOutcome::Status(1);
+
+            continue;
+        }
+
+        io.println(format!("[{}]{} {} &", job.id, marker, job.command));
+    }
+
+    output
+};
@@ -0,0 +1,108 @@
+use std::path::Path;
+
+use br_data::{
+    command::{BrBuiltin, CommandIo, ExecOutcome, ExecuteFn, TabCompletion, TabCompletionFn, TabCompletionType},
+    context::Context,
+    dirs,
+};
+
+lazy_static! {
+    pub static ref CMD: BrBuiltin = BrBuiltin {
+        name: "bookmark",
+        tab_completion: TabCompletionType::Static(vec![
+            TabCompletion::new("add", TabCompletionType::None),
+            TabCompletion::new("del", TabCompletionType::Dynamic(tc_bookmark_list)),
+            TabCompletion::new("list", TabCompletionType::None),
+        ]),
+        execute,
+    };
+}
+
+#[allow(non_upper_case_globals)]
+const tc_bookmark_list: TabCompletionFn = |args: Vec<String>, ctx: &Context| -> Vec<String> {
+    if args.len() > 1 {
+        return Vec::new();
+    }
+
+    let cur_arg = args.first().cloned().unwrap_or_default();
+    ctx.bookmarks.marks.keys().filter(|name| name.starts_with(&cur_arg)).cloned().collect()
+};
+
+#[allow(non_upper_case_globals)]
+const execute: ExecuteFn = |args: Vec<String>, ctx: &mut Context, io: &mut CommandIo| -> ExecOutcome {
+    let operator = args.first().map_or("list", String::as_str);
+
+    match (operator, args.len()) {
+        ("list", 1) | ("list", 0) => {
+            for (name, dir) in &ctx.bookmarks.marks {
+                io.println(format!("{:<12} {}", name, dir));
+            }
+
+            ExecOutcome::Status(0)
+        },
+        ("add", 2) | ("add", 3) => add_bookmark(&args[1], args.get(2), ctx, io),
+        ("del", 2) => {
+            if ctx.bookmarks.marks.remove(&args[1]).is_none() {
+                io.eprintln(format!("Unable to find bookmark '{}'!", args[1]));
+
+                return ExecOutcome::Status(1);
+            }
+
+            save_bookmarks(ctx, io)
+        },
+        ("add", _) | ("del", _) | ("list", _) => {
+            io.eprintln("Invalid arguments! Expected 'bookmark add <name> [dir]', 'bookmark del <name>' or 'bookmark list'");
+
+            ExecOutcome::Status(1)
+        },
+        _ => {
+            io.eprintln("Invalid argument at pos 1! Expected one of 'add', 'del' or 'list'");
+
+            ExecOutcome::Status(1)
+        },
+    }
+};
+
+// Bookmark a directory, the current directory if none is given
+fn add_bookmark(name: &str, dir: Option<&String>, ctx: &mut Context, io: &mut CommandIo) -> ExecOutcome {
+    if let Some(pos) = name.chars().position(|ch| !(ch.is_alphanumeric() || ch == '_' || ch == '-')) {
+        let invalid_char = name.chars().nth(pos).unwrap_or_default();
+        io.eprintln(format!("Invalid character at position {}, '{}'", pos, invalid_char));
+
+        return ExecOutcome::Status(1);
+    }
+
+    let current = dirs::current_dir();
+    let dir = match dir {
+        Some(dir) => dirs::normalize(&current.join(Path::new(dir))),
+        None => current,
+    };
+
+    if !dir.is_dir() {
+        io.eprintln(format!("Unable to find directory '{}'!", dir.display()));
+
+        return ExecOutcome::Status(1);
+    }
+
+    // Bookmarks are saved one per line separated by a tab
+    let dir = dir.to_string_lossy().to_string();
+    if dir.contains(['\t', '\n']) {
+        io.eprintln(format!("Unable to bookmark '{}'! Directory contains a tab or newline", dir.escape_default()));
+
+        return ExecOutcome::Status(1);
+    }
+
+    ctx.bookmarks.marks.insert(name.to_string(), dir);
+
+    save_bookmarks(ctx, io)
+}
+
+fn save_bookmarks(ctx: &mut Context, io: &mut CommandIo) -> ExecOutcome {
+    if let Err(why) = ctx.bookmarks.save() {
+        io.eprintln(format!("Unable to save bookmarks! {}", why));
+
+        return ExecOutcome::Status(1);
+    }
+
+    ExecOutcome::Status(0)
+}
@@ -1,8 +1,12 @@
-use std::{env, path::Path};
+use std::{
+    env,
+    path::{Path, PathBuf},
+};
 
 use br_data::{
-    command::{BrBuiltin, ExecuteFn, TabCompletionType},
+    command::{BrBuiltin, CommandIo, ExecOutcome, ExecuteFn, TabCompletionType},
     context::Context,
+    dirs,
 };
 
 pub const CMD: BrBuiltin = BrBuiltin {
@@ -12,110 +16,143 @@ pub const CMD: BrBuiltin = BrBuiltin {
 };
 
 #[allow(non_upper_case_globals)]
-const execute: ExecuteFn = |args: Vec<String>, _ctx: &mut Context| -> i32 {
-    let mut dirs = get_prev_dirs().unwrap_or_default();
-    let mut dir_idx = get_dir_idx().unwrap_or(dirs.len() as usize);
-
-    let original_dir = args.into_iter().peekable().peek().map_or("~", |dir| dir).to_string();
-    let mut new_dir;
-    match original_dir.as_ref() {
-        "-" => {
-            if dir_idx == 0 {
-                println!("Already at end of dir history!");
-                return 0;
-            }
-
-            dir_idx -= 1;
-            match dirs.get(dir_idx) {
-                Some(dir) => new_dir = dir.clone(),
-                None => {
-                    println!("Unable to get previous dir!");
-                    return 0;
-                },
-            }
-        },
-        "+" => {
-            if dir_idx == dirs.len() {
-                println!("Already at start of dir history!");
-                return 0;
-            }
-
-            dir_idx += 1;
-            match dirs.get(dir_idx) {
-                Some(dir) => new_dir = dir.clone(),
-                None => {
-                    println!("Unable to get next dir!");
-                    return 0;
-                },
-            }
-        },
-        _ => {
-            new_dir = original_dir.clone();
-        },
+const execute: ExecuteFn = |args: Vec<String>, ctx: &mut Context, io: &mut CommandIo| -> ExecOutcome {
+    // '-P' resolves symlinks, '-L' (the default) keeps them
+    let mut physical = false;
+    let mut target = None;
+    for arg in args {
+        match arg.as_str() {
+            "-P" => physical = true,
+            "-L" => physical = false,
+            _ if target.is_none() => target = Some(arg),
+            _ => {
+                io.eprintln("Invalid arguments! Expected at most 1 directory");
+
+                return ExecOutcome::Status(1);
+            },
+        }
     }
 
-    if new_dir.starts_with('~') {
-        match home::home_dir() {
-            Some(home_dir) => {
-                new_dir.remove(0);
-                if let Some(home) = home_dir.to_str() {
-                    new_dir = format!("{}{}", home, &new_dir);
-                } else {
-                    println!("Unable to get home directory!");
-                    return 1;
-                }
-            },
+    let target = target.unwrap_or_else(|| String::from("~"));
+
+    // '-N' moves back N directories in the history and '+N'
+    // forward again, N defaults to 1
+    if let Some(count) = history_count(&target, '-') {
+        let dir = match ctx.dir_history.peek_back(count) {
+            Some(dir) => dir.clone(),
             None => {
-                println!("Unable to get home directory!");
-                return 1;
+                io.eprintln("Already at end of dir history!");
+                return ExecOutcome::Status(1);
             },
-        }
+        };
+
+        return match set_dir(Path::new(&dir), physical) {
+            Ok(old_dir) => {
+                ctx.dir_history.move_back(count, old_dir);
+                ExecOutcome::Status(0)
+            },
+            Err(why) => {
+                io.eprintln(why);
+                ExecOutcome::Status(3)
+            },
+        };
     }
 
-    let old_dir = env::current_dir();
-    let path = Path::new(&new_dir);
+    if let Some(count) = history_count(&target, '+') {
+        let dir = match ctx.dir_history.peek_forward(count) {
+            Some(dir) => dir.clone(),
+            None => {
+                io.eprintln("Already at start of dir history!");
+                return ExecOutcome::Status(1);
+            },
+        };
+
+        return match set_dir(Path::new(&dir), physical) {
+            Ok(old_dir) => {
+                ctx.dir_history.move_forward(count, old_dir);
+                ExecOutcome::Status(0)
+            },
+            Err(why) => {
+                io.eprintln(why);
+                ExecOutcome::Status(3)
+            },
+        };
+    }
 
-    if let Err(why) = env::set_current_dir(path) {
-        println!("Unable to move to directory! {}", why);
-        return 3;
+    // Show where directories found in CDPATH are, other commands
+    // moving directory don't
+    let found = dirs::expand_home(&target).and_then(|dir| dirs::search_cdpath(ctx, &dir));
+    let target = match &found {
+        Some(path) => path.to_string_lossy().to_string(),
+        None => target,
+    };
+
+    let output = change_dir(ctx, io, &target, physical);
+    if output.is_success() && found.is_some() {
+        io.println(target);
     }
 
-    if original_dir != "-" && original_dir != "+" {
-        if dir_idx != dirs.len() {
-            let _ = dirs.split_off(dir_idx);
-        }
+    output
+};
 
-        dir_idx += 1;
+fn history_count(arg: &str, prefix: char) -> Option<usize> {
+    let count = arg.strip_prefix(prefix)?;
+    if count.is_empty() {
+        return Some(1);
     }
 
-    match old_dir {
-        Ok(old) => dirs.push(old.to_str().unwrap().to_string()),
-        Err(why) => {
-            println!("Unable to save old dir! {}", why);
-            return 2;
+    count.parse::<usize>().ok().filter(|count| *count > 0)
+}
+
+// Move to a directory, expanding '~' and searching CDPATH, and
+// record the previous directory in the history
+pub fn change_dir(ctx: &mut Context, io: &mut CommandIo, dir: &str, physical: bool) -> ExecOutcome {
+    match move_to_dir(ctx, io, dir, physical) {
+        Ok(old_dir) => {
+            ctx.dir_history.push(old_dir);
+            ExecOutcome::Status(0)
         },
+        Err(output) => output,
     }
+}
 
-    set_prev_dirs(dirs);
-    set_dir_idx(dir_idx);
+// Same as change_dir but leaves the history alone, returns the
+// directory that was left
+pub fn move_to_dir(ctx: &Context, io: &mut CommandIo, dir: &str, physical: bool) -> Result<String, ExecOutcome> {
+    let dir = match dirs::expand_home(dir) {
+        Some(dir) => dir,
+        None => {
+            io.eprintln("Unable to get home directory!");
+            return Err(ExecOutcome::Status(1));
+        },
+    };
 
-    0
-};
+    let path = dirs::search_cdpath(ctx, &dir).unwrap_or_else(|| PathBuf::from(&dir));
 
-fn get_dir_idx() -> Option<usize> {
-    let dir_idx = env::var("BRANSH_CUR_DIR_IDX").ok()?;
-    dir_idx.parse::<usize>().ok()
+    set_dir(&path, physical).map_err(|why| {
+        io.eprintln(why);
+        ExecOutcome::Status(3)
+    })
 }
 
-fn get_prev_dirs() -> Option<Vec<String>> {
-    let dir = env::var("BRANSH_PREV_DIRS").ok()?;
-    Some(dir.split(':').map(|dir| dir.to_string()).collect())
-}
+// Change directory and update PWD and OLDPWD, returns the
+// directory that was left
+fn set_dir(dir: &Path, physical: bool) -> Result<String, String> {
+    let old_dir = dirs::current_dir();
+    let new_dir = if physical { dir.to_path_buf() } else { dirs::normalize(&old_dir.join(dir)) };
 
-fn set_prev_dirs(dirs: Vec<String>) {
-    env::set_var("BRANSH_PREV_DIRS", dirs.join(":"));
-}
+    if let Err(why) = env::set_current_dir(&new_dir) {
+        return Err(format!("Unable to move to directory! {}", why));
+    }
+
+    let pwd = if physical {
+        env::current_dir().map_err(|why| format!("Unable to get current directory! {}", why))?
+    } else {
+        new_dir
+    };
+
+    env::set_var("OLDPWD", &old_dir);
+    env::set_var("PWD", &pwd);
 
-fn set_dir_idx(dir_idx: usize) {
-    env::set_var("BRANSH_CUR_DIR_IDX", dir_idx.to_string());
+    Ok(old_dir.to_string_lossy().to_string())
 }
@@ -0,0 +1,18 @@
+use br_data::{
+    command::{BrBuiltin, CommandIo, ExecOutcome, ExecuteFn, TabCompletionType},
+    context::Context,
+};
+
+pub const CMD: BrBuiltin = BrBuiltin {
+    name: "command",
+    tab_completion: TabCompletionType::None,
+    execute,
+};
+
+#[allow(non_upper_case_globals)]
+const execute: ExecuteFn = |_args: Vec<String>, _ctx: &mut Context, _io: &mut CommandIo| -> ExecOutcome {
+    // Dummy command as 'command name' is unwrapped while
+    // expanding aliases in br-executer, this is soley for
+    // tab completion and validating commands
+    ExecOutcome::Status(0)
+};
@@ -0,0 +1,64 @@
+use std::env;
+
+use br_data::{
+    command::{BrBuiltin, CommandIo, ExecOutcome, ExecuteFn, TabCompletion, TabCompletionType},
+    context::Context,
+    dirs::current_dir,
+};
+
+lazy_static! {
+    pub static ref CMD: BrBuiltin = BrBuiltin {
+        name: "dirs",
+        tab_completion: TabCompletionType::Static(vec![
+            TabCompletion::new("-c", TabCompletionType::None),
+            TabCompletion::new("-p", TabCompletionType::None),
+            TabCompletion::new("-v", TabCompletionType::None),
+        ]),
+        execute,
+    };
+}
+
+#[allow(non_upper_case_globals)]
+const execute: ExecuteFn = |args: Vec<String>, ctx: &mut Context, io: &mut CommandIo| -> ExecOutcome {
+    match args.first().map(String::as_str) {
+        None => print_stack(ctx, io),
+        Some("-c") => ctx.dir_history.replace(Vec::new()),
+        Some("-p") => {
+            for dir in stack_entries(ctx) {
+                io.println(dir);
+            }
+        },
+        Some("-v") => {
+            for (idx, dir) in stack_entries(ctx).into_iter().enumerate() {
+                io.println(format!("{:>2}  {}", idx, dir));
+            }
+        },
+        Some(arg) => {
+            io.eprintln(format!("Invalid argument '{}'!", arg));
+
+            return ExecOutcome::Status(1);
+        },
+    }
+
+    ExecOutcome::Status(0)
+};
+
+pub fn print_stack(ctx: &Context, io: &mut CommandIo) {
+    io.println(stack_entries(ctx).join(" "));
+}
+
+// The current directory followed by the stack, with the home
+// directory shortened to '~'. The index of each is the N used
+// by 'cd -N'
+fn stack_entries(ctx: &Context) -> Vec<String> {
+    let current = current_dir().to_string_lossy().to_string();
+    let home = env::var("HOME").unwrap_or_default();
+
+    std::iter::once(&current)
+        .chain(ctx.dir_history.entries())
+        .map(|dir| match dir.strip_prefix(&home) {
+            Some(rest) if !home.is_empty() => format!("~{}", rest),
+            _ => dir.clone(),
+        })
+        .collect()
+}
@@ -0,0 +1,38 @@
+use br_data::{
+    command::{BrBuiltin, CommandIo, ExecOutcome, ExecuteFn, TabCompletionType},
+    context::Context,
+};
+
+use super::jobs::tc_job_list;
+
+pub const CMD: BrBuiltin = BrBuiltin {
+    name: "disown",
+    tab_completion: TabCompletionType::Dynamic(tc_job_list),
+    execute,
+};
+
+#[allow(non_upper_case_globals)]
+const execute: ExecuteFn = |args: Vec<String>, ctx: &mut Context, io: &mut CommandIo| -> ExecOutcome {
+    if args.iter().any(|arg| arg == "-a") {
+        ctx.jobs = Default::default();
+
+        return ExecOutcome::Status(0);
+    }
+
+    let specs = if args.is_empty() { vec![None] } else { args.iter().map(|arg| Some(arg.as_str())).collect() };
+
+    let mut output = ExecOutcome::Status(0);
+    for spec in specs {
+        match ctx.jobs.find(spec) {
+            Some(id) => {
+                ctx.jobs.remove(id);
+            },
+            None => {
+                io.eprintln(format!("Unable to find job '{}'!", spec.unwrap_or("%+")));
+                output = ExecOutcome::Status(1);
+            },
+        }
+    }
+
+    output
+};
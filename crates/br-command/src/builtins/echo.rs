@@ -0,0 +1,123 @@
+use br_data::{
+    command::{BrBuiltin, CommandIo, ExecOutcome, ExecuteFn, TabCompletionType},
+    context::Context,
+};
+
+use super::printf::unescape;
+
+pub const CMD: BrBuiltin = BrBuiltin {
+    name: "echo",
+    tab_completion: TabCompletionType::None,
+    execute,
+};
+
+#[allow(non_upper_case_globals)]
+const execute: ExecuteFn = |mut args: Vec<String>, _ctx: &mut Context, io: &mut CommandIo| -> ExecOutcome {
+    // '-n' skips the newline, '-e' enables escapes and '-E'
+    // disables them again, anything else is printed
+    let mut newline = true;
+    let mut escapes = false;
+    while let Some(arg) = args.first() {
+        let flags = match arg.strip_prefix('-') {
+            Some(flags) if !flags.is_empty() && flags.chars().all(|flag| "neE".contains(flag)) => flags,
+            _ => break,
+        };
+
+        for flag in flags.chars() {
+            match flag {
+                'n' => newline = false,
+                'e' => escapes = true,
+                _ => escapes = false,
+            }
+        }
+
+        args.remove(0);
+    }
+
+    let mut text = args.join(" ");
+    if escapes {
+        // '\c' stops all further output, including the newline
+        let (unescaped, stop) = unescape(&text, true);
+        text = unescaped;
+        newline &= !stop;
+    }
+
+    if newline {
+        text.push('\n');
+    }
+
+    io.print(text);
+
+    ExecOutcome::Status(0)
+};
+
+// Make cargo stop complaining about functions used for
+// tests
+#[allow(unused_imports, dead_code)]
+mod test {
+    use std::{
+        cell::RefCell,
+        io::{self, Write},
+        rc::Rc,
+    };
+
+    use br_data::{
+        command::{CommandIo, ExecOutcome},
+        context::Context,
+    };
+
+    use super::execute;
+
+    // Writer sharing its buffer so the output can be read back
+    #[derive(Clone, Default)]
+    struct Buffer(Rc<RefCell<Vec<u8>>>);
+
+    impl Write for Buffer {
+        fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+            self.0.borrow_mut().write(buf)
+        }
+
+        fn flush(&mut self) -> io::Result<()> {
+            Ok(())
+        }
+    }
+
+    fn echo(args: &[&str]) -> String {
+        let buffer = Buffer::default();
+        let mut io = CommandIo {
+            stdout: Box::new(buffer.clone()),
+            ..CommandIo::default()
+        };
+
+        let args = args.iter().map(|arg| arg.to_string()).collect();
+        assert_eq!(execute(args, &mut Context::default(), &mut io), ExecOutcome::Status(0));
+        drop(io);
+
+        let output = buffer.0.borrow().clone();
+        String::from_utf8(output).unwrap_or_default()
+    }
+
+    #[test]
+    fn plain() {
+        assert_eq!(echo(&[]), "\n");
+        assert_eq!(echo(&["a", "b  c"]), "a b  c\n");
+        assert_eq!(echo(&["a\\tb"]), "a\\tb\n");
+    }
+
+    #[test]
+    fn flags() {
+        assert_eq!(echo(&["-n", "a"]), "a");
+        assert_eq!(echo(&["-e", "a\\tb\\x41"]), "a\tbA\n");
+        assert_eq!(echo(&["-ne", "a\\n"]), "a\n");
+        assert_eq!(echo(&["-e", "-E", "a\\n"]), "a\\n\n");
+        assert_eq!(echo(&["-e", "a\\cb"]), "a");
+    }
+
+    #[test]
+    fn not_flags() {
+        assert_eq!(echo(&["-", "a"]), "- a\n");
+        assert_eq!(echo(&["-x", "a"]), "-x a\n");
+        assert_eq!(echo(&["a", "-n"]), "a -n\n");
+        assert_eq!(echo(&["-n-", "a"]), "-n- a\n");
+    }
+}
@@ -0,0 +1,73 @@
+use std::{io, process::Command};
+
+use br_data::{
+    command::{BrBuiltin, CommandIo, ExecOutcome, ExecuteFn, TabCompletionType},
+    context::Context,
+};
+
+pub const CMD: BrBuiltin = BrBuiltin {
+    name: "exec",
+    tab_completion: TabCompletionType::None,
+    execute,
+};
+
+// Redirections without a command are applied to the shell by
+// the executer so there's nothing left to do here
+#[allow(non_upper_case_globals)]
+const execute: ExecuteFn = |args: Vec<String>, ctx: &mut Context, io: &mut CommandIo| -> ExecOutcome {
+    if args.is_empty() {
+        return ExecOutcome::Status(0);
+    }
+
+    let mut command = Command::new(&args[0]);
+    command.args(&args[1..]).envs(ctx.exported_variables());
+    io.flush();
+
+    let why = replace_shell(command, ctx);
+    io.eprintln(format!("Unable to execute command! {}", why));
+
+    if why.kind() == io::ErrorKind::NotFound {
+        ExecOutcome::Status(127)
+    } else {
+        ExecOutcome::Status(126)
+    }
+};
+
+// Only returns if the command couldn't be run
+#[cfg(unix)]
+fn replace_shell(mut command: Command, ctx: &mut Context) -> io::Error {
+    use std::os::unix::process::CommandExt;
+
+    use br_data::{job, signal};
+
+    job::reset_signals();
+    let why = command.exec();
+
+    // Restore the signals the shell was using
+    for sig in [
+        libc::SIGINT,
+        libc::SIGQUIT,
+        libc::SIGHUP,
+        libc::SIGTERM,
+        libc::SIGTSTP,
+        libc::SIGTTIN,
+        libc::SIGTTOU,
+    ] {
+        match ctx.traps.get(&sig) {
+            Some(handler) if handler.is_empty() => signal::ignore(sig),
+            Some(_) => signal::catch(sig),
+            None => signal::reset(sig),
+        }
+    }
+
+    why
+}
+
+// Windows has no exec so run the command and exit with its code
+#[cfg(windows)]
+fn replace_shell(mut command: Command, _ctx: &mut Context) -> io::Error {
+    match command.status() {
+        Ok(status) => std::process::exit(status.code().unwrap_or(1)),
+        Err(why) => why,
+    }
+}
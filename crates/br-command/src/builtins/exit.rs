@@ -1,5 +1,5 @@
 use br_data::{
-    command::{BrBuiltin, ExecuteFn, TabCompletionType},
+    command::{BrBuiltin, CommandIo, ExecOutcome, ExecuteFn, TabCompletionType},
     context::Context,
 };
 
@@ -10,8 +10,24 @@ pub const CMD: BrBuiltin = BrBuiltin {
 };
 
 #[allow(non_upper_case_globals)]
-const execute: ExecuteFn = |_args: Vec<String>, _ctx: &mut Context| -> i32 {
-    // Dummy command as exit is hardcoded into br-executer,
-    // this is soley for tab completion and validating commands
-    0
+const execute: ExecuteFn = |args: Vec<String>, ctx: &mut Context, io: &mut CommandIo| -> ExecOutcome {
+    if args.len() > 1 {
+        io.eprintln(format!("Invalid arguments! Expected 0-1, got {}", args.len()));
+
+        return ExecOutcome::Status(1);
+    }
+
+    let exit_code = match args.first() {
+        Some(code) => match code.parse::<i32>() {
+            Ok(code) => code,
+            Err(_) => {
+                io.eprintln(format!("Invalid exit code '{}'! Expected a number", code));
+
+                return ExecOutcome::Status(1);
+            },
+        },
+        None => ctx.last_status,
+    };
+
+    ExecOutcome::Exit(exit_code)
 };
@@ -0,0 +1,80 @@
+use br_data::{
+    command::{BrBuiltin, CommandIo, ExecOutcome, ExecuteFn, TabCompletionType},
+    context::Context,
+    variable::VarValue,
+};
+
+use super::get::tc_var_list;
+
+pub const CMD: BrBuiltin = BrBuiltin {
+    name: "export",
+    tab_completion: TabCompletionType::Dynamic(tc_var_list),
+    execute,
+};
+
+#[allow(non_upper_case_globals)]
+const execute: ExecuteFn = |mut args: Vec<String>, ctx: &mut Context, io: &mut CommandIo| -> ExecOutcome {
+    if args.is_empty() {
+        let mut exported = ctx.exported_variables();
+        exported.sort();
+
+        for (name, value) in exported {
+            io.println(format!("{} \"{}\"", name, value));
+        }
+
+        return ExecOutcome::Status(0);
+    }
+
+    // '-n' stops a variable from being exported
+    let exported = args[0] != "-n";
+    if !exported {
+        args.remove(0);
+    }
+
+    if args.is_empty() || args.len() > 2 || (!exported && args.len() != 1) {
+        io.eprintln(format!("Invalid arguments! Expected 1-2, got {}", args.len()));
+
+        return ExecOutcome::Status(1);
+    }
+
+    let var_name = args[0].clone();
+
+    // Ensure alphanumeric or '_'
+    if let Some(pos) = var_name
+        .chars()
+        .position(|ch| !((ch.is_alphanumeric() || ch == '_') && ch != ' '))
+    {
+        let invalid_char = var_name.chars().nth(pos).unwrap_or_default();
+        io.eprintln(format!("Invalid character at position {}, '{}'", pos, invalid_char));
+
+        return ExecOutcome::Status(1);
+    }
+
+    if let Some(value) = args.get(1) {
+        if ctx.is_readonly(&var_name) {
+            io.eprintln(format!("Unable to set '{}'! Variable is read only", var_name));
+
+            return ExecOutcome::Status(1);
+        }
+
+        let value = VarValue::Str(value.clone());
+        if let Err(why) = ctx.validate_variable(&var_name, &value) {
+            io.eprintln(why);
+
+            return ExecOutcome::Status(1);
+        }
+
+        ctx.set_variable_value(&var_name, value);
+    }
+
+    match ctx.variables.get_mut(&var_name) {
+        Some(var) => var.exported = exported,
+        None => {
+            io.eprintln(format!("Unable to find variable '{}'!", var_name));
+
+            return ExecOutcome::Status(1);
+        },
+    }
+
+    ExecOutcome::Status(0)
+};
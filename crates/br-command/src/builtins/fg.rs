@@ -0,0 +1,74 @@
+use br_data::{
+    command::{BrBuiltin, CommandIo, ExecOutcome, ExecuteFn, TabCompletionType},
+    context::Context,
+    job::{self, JobState},
+};
+
+use super::jobs::tc_job_list;
+
+pub const CMD: BrBuiltin = BrBuiltin {
+    name: "fg",
+    tab_completion: TabCompletionType::Dynamic(tc_job_list),
+    execute,
+};
+
+#[allow(non_upper_case_globals)]
+const execute: ExecuteFn = |args: Vec<String>, ctx: &mut Context, io: &mut CommandIo| -> ExecOutcome {
+    if args.len() > 1 {
+        io.eprintln(format!("Invalid arguments! Expected 0-1, got {}", args.len()));
+
+        return ExecOutcome::Status(1);
+    }
+
+    let id = match ctx.jobs.find(args.first().map(String::as_str)) {
+        Some(id) => id,
+        None => {
+            io.eprintln("No such job!");
+
+            return ExecOutcome::Status(1);
+        },
+    };
+
+    let job_control = ctx.job_control;
+    let job = match ctx.jobs.get_mut(id) {
+        Some(job) => job,
+        None => return ExecOutcome::Status(1),
+    };
+
+    io.println(&job.command);
+    io.flush();
+
+    if job_control {
+        job::give_terminal(job.pgid);
+    }
+
+    if let Err(why) = job::continue_job(job) {
+        if job_control {
+            job::take_terminal();
+        }
+
+        io.eprintln(format!("Unable to continue job! {}", why));
+
+        return ExecOutcome::Status(1);
+    }
+
+    if let Err(why) = job::wait_foreground(job, job_control) {
+        io.eprintln(format!("Unable to wait for job! {}", why));
+
+        return ExecOutcome::Status(1);
+    }
+
+    if job.state() == JobState::Stopped {
+        ctx.jobs.set_current(id);
+        if let Some(job) = ctx.jobs.get(id) {
+            io.eprintln(format!("\n{}", ctx.jobs.format(job)));
+        }
+
+        return ExecOutcome::Status(128 + libc::SIGTSTP);
+    }
+
+    let outcome = job.outcome();
+    ctx.jobs.remove(id);
+
+    outcome
+};
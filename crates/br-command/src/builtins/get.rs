@@ -1,8 +1,10 @@
 use std::env;
 
 use br_data::{
-    command::{BrBuiltin, ExecuteFn, TabCompletionFn, TabCompletionType},
+    command::{BrBuiltin, CommandIo, ExecOutcome, ExecuteFn, TabCompletionFn, TabCompletionType},
     context::Context,
+    setting::{find_setting, Setting, SETTINGS},
+    variable::VarValue,
 };
 
 pub const CMD: BrBuiltin = BrBuiltin {
@@ -14,7 +16,7 @@ pub const CMD: BrBuiltin = BrBuiltin {
 #[allow(non_upper_case_globals)]
 pub const tc_var_list: TabCompletionFn = |args: Vec<String>, ctx: &Context| -> Vec<String> {
     if args.len() <= 1 {
-        let mut cur_arg = if let Some(arg) = args.get(0) {
+        let mut cur_arg = if let Some(arg) = args.first() {
             arg.clone()
         } else {
             String::new()
@@ -39,18 +41,25 @@ pub const tc_var_list: TabCompletionFn = |args: Vec<String>, ctx: &Context| -> V
 };
 
 #[allow(non_upper_case_globals)]
-const execute: ExecuteFn = |args: Vec<String>, ctx: &mut Context| -> i32 {
+const execute: ExecuteFn = |mut args: Vec<String>, ctx: &mut Context, io: &mut CommandIo| -> ExecOutcome {
+    // '-d' shows the documentation of settings
+    if args.first().map(String::as_str) == Some("-d") {
+        args.remove(0);
+        return describe_settings(args, io);
+    }
+
     if args.len() > 1 {
-        println!("Invalid arguments! Expected less than 2, got {}", args.len());
+        io.eprintln(format!("Invalid arguments! Expected less than 2, got {}", args.len()));
 
-        return 1;
+        return ExecOutcome::Status(1);
     }
 
     if args.is_empty() {
-        let keys = ctx.variables.keys();
-        println!("{:?}", keys);
+        let mut keys: Vec<&String> = ctx.variables.keys().collect();
+        keys.sort();
+        io.println(format!("{:?}", keys));
 
-        return 0;
+        return ExecOutcome::Status(0);
     }
 
     let mut var_name = args[0].clone();
@@ -69,13 +78,59 @@ const execute: ExecuteFn = |args: Vec<String>, ctx: &mut Context| -> i32 {
         .position(|ch| !((ch.is_alphanumeric() || ch == '_') && ch != ' '))
     {
         let invalid_char = var_name.chars().nth(pos).unwrap_or_default();
-        println!("Invalid character at position {}, '{}'", pos, invalid_char);
+        io.eprintln(format!("Invalid character at position {}, '{}'", pos, invalid_char));
 
-        return 1;
+        return ExecOutcome::Status(1);
     }
 
-    let val = ctx.get_variable(&var_name, String::new(), is_env);
-    println!("{}", val);
+    // Lists and maps are printed one item per line
+    match ctx.get_variable_value(&var_name, is_env) {
+        Some(VarValue::List(items)) => {
+            for item in items {
+                io.println(item);
+            }
+        },
+        Some(VarValue::Map(map)) => {
+            for (key, value) in map {
+                io.println(format!("{}: {}", key, value));
+            }
+        },
+        Some(value) => io.println(value),
+        None => io.println(""),
+    }
 
-    0
+    ExecOutcome::Status(0)
 };
+
+fn describe_settings(args: Vec<String>, io: &mut CommandIo) -> ExecOutcome {
+    if args.is_empty() {
+        for setting in SETTINGS {
+            describe_setting(setting, io);
+        }
+
+        return ExecOutcome::Status(0);
+    }
+
+    let mut output = ExecOutcome::Status(0);
+    for name in args {
+        match find_setting(&name) {
+            Some(setting) => describe_setting(setting, io),
+            None => {
+                io.eprintln(format!("Unknown setting '{}'!", name));
+                output = ExecOutcome::Status(1);
+            },
+        }
+    }
+
+    output
+}
+
+fn describe_setting(setting: &Setting, io: &mut CommandIo) {
+    io.println(format!(
+        "{} ({}, default \"{}\")",
+        setting.name,
+        setting.setting_type.name(),
+        setting.default
+    ));
+    io.println(format!("    {}", setting.description));
+}
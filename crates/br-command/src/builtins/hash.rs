@@ -0,0 +1,48 @@
+use br_data::{
+    command::{BrBuiltin, CommandIo, ExecOutcome, ExecuteFn, TabCompletionType},
+    context::Context,
+};
+
+pub const CMD: BrBuiltin = BrBuiltin {
+    name: "hash",
+    tab_completion: TabCompletionType::None,
+    execute,
+};
+
+#[allow(non_upper_case_globals)]
+const execute: ExecuteFn = |args: Vec<String>, ctx: &mut Context, io: &mut CommandIo| -> ExecOutcome {
+    if args.is_empty() {
+        let used = ctx.commands.borrow().used();
+        if used.is_empty() {
+            io.println("No commands have been cached");
+
+            return ExecOutcome::Status(0);
+        }
+
+        io.println("hits    command");
+        for (_, command) in used {
+            io.println(format!("{:>4}    {}", command.hits, command.path.display()));
+        }
+
+        return ExecOutcome::Status(0);
+    }
+
+    if args[0] == "-r" {
+        ctx.commands.borrow_mut().clear();
+
+        return ExecOutcome::Status(0);
+    }
+
+    let mut output = ExecOutcome::Status(0);
+    for name in args {
+        match ctx.commands.borrow_mut().lookup(&name) {
+            Some(path) => io.println(path.display()),
+            None => {
+                io.eprintln(format!("Unable to find command '{}'!", name));
+                output = ExecOutcome::Status(1);
+            },
+        }
+    }
+
+    output
+};
@@ -0,0 +1,109 @@
+use br_data::{
+    command::{BrBuiltin, CommandIo, ExecOutcome, ExecuteFn, TabCompletion, TabCompletionFn, TabCompletionType},
+    context::Context,
+    hook::HookEvent,
+};
+
+lazy_static! {
+    pub static ref CMD: BrBuiltin = BrBuiltin {
+        name: "hook",
+        tab_completion: TabCompletionType::Static(vec![
+            TabCompletion::new("add", TabCompletionType::Dynamic(tc_event_list)),
+            TabCompletion::new("del", TabCompletionType::Dynamic(tc_event_list)),
+            TabCompletion::new("list", TabCompletionType::Dynamic(tc_event_list)),
+        ]),
+        execute,
+    };
+}
+
+#[allow(non_upper_case_globals)]
+const tc_event_list: TabCompletionFn = |args: Vec<String>, _ctx: &Context| -> Vec<String> {
+    if args.len() > 1 {
+        return Vec::new();
+    }
+
+    let cur_arg = args.first().cloned().unwrap_or_default();
+    HookEvent::ALL
+        .iter()
+        .map(|event| event.name().to_string())
+        .filter(|name| name.starts_with(&cur_arg))
+        .collect()
+};
+
+#[allow(non_upper_case_globals)]
+const execute: ExecuteFn = |mut args: Vec<String>, ctx: &mut Context, io: &mut CommandIo| -> ExecOutcome {
+    if args.is_empty() {
+        return list_hooks(None, ctx, io);
+    }
+
+    let operator = args.remove(0);
+
+    let event = match args.first() {
+        Some(name) => match HookEvent::from_name(name) {
+            Some(event) => Some(event),
+            None => {
+                let names: Vec<&str> = HookEvent::ALL.iter().map(HookEvent::name).collect();
+                io.eprintln(format!("Invalid hook '{}'! Expected one of '{}'", name, names.join("', '")));
+
+                return ExecOutcome::Status(1);
+            },
+        },
+        None => None,
+    };
+
+    match (operator.as_ref(), event) {
+        ("list", event) => list_hooks(event, ctx, io),
+        ("add", Some(event)) if args.len() == 2 => {
+            ctx.hooks.entry(event).or_default().push(args.remove(1));
+
+            ExecOutcome::Status(0)
+        },
+        ("del", Some(event)) => del_hook(event, args.get(1), ctx, io),
+        ("add", _) | ("del", _) => {
+            io.eprintln(format!("Invalid arguments! Expected 'hook {} <event> <command>'", operator));
+
+            ExecOutcome::Status(1)
+        },
+        _ => {
+            io.eprintln("Invalid argument at pos 1! Expected one of 'add', 'del' or 'list'");
+
+            ExecOutcome::Status(1)
+        },
+    }
+};
+
+// Remove one command from a hook, or all of them when no
+// command is given
+fn del_hook(event: HookEvent, command: Option<&String>, ctx: &mut Context, io: &mut CommandIo) -> ExecOutcome {
+    let commands = ctx.hooks.entry(event).or_default();
+    match command {
+        Some(command) => match commands.iter().position(|cmd| cmd == command) {
+            Some(pos) => {
+                commands.remove(pos);
+            },
+            None => {
+                io.eprintln(format!("Unable to find '{}' in hook '{}'!", command, event.name()));
+
+                return ExecOutcome::Status(1);
+            },
+        },
+        None => commands.clear(),
+    }
+
+    ExecOutcome::Status(0)
+}
+
+fn list_hooks(event: Option<HookEvent>, ctx: &mut Context, io: &mut CommandIo) -> ExecOutcome {
+    let events = match event {
+        Some(event) => vec![event],
+        None => HookEvent::ALL.to_vec(),
+    };
+
+    for event in events {
+        for command in ctx.hooks.get(&event).into_iter().flatten() {
+            io.println(format!("{}: {}", event.name(), command));
+        }
+    }
+
+    ExecOutcome::Status(0)
+}
@@ -0,0 +1,85 @@
+use br_data::{
+    command::{BrBuiltin, CommandIo, ExecOutcome, ExecuteFn, TabCompletion, TabCompletionFn, TabCompletionType},
+    context::Context,
+};
+
+lazy_static! {
+    pub static ref CMD: BrBuiltin = BrBuiltin {
+        name: "jobs",
+        tab_completion: TabCompletionType::Static(vec![
+            TabCompletion::new("-l", TabCompletionType::None),
+            TabCompletion::new("-p", TabCompletionType::None),
+        ]),
+        execute,
+    };
+}
+
+#[allow(non_upper_case_globals)]
+pub const tc_job_list: TabCompletionFn = |args: Vec<String>, ctx: &Context| -> Vec<String> {
+    let cur_arg = args.last().cloned().unwrap_or_default();
+    ctx.jobs
+        .jobs
+        .iter()
+        .map(|job| format!("%{}", job.id))
+        .filter(|spec| spec.starts_with(&cur_arg))
+        .collect()
+};
+
+#[allow(non_upper_case_globals)]
+const execute: ExecuteFn = |args: Vec<String>, ctx: &mut Context, io: &mut CommandIo| -> ExecOutcome {
+    let mut show_pids = false;
+    let mut only_pids = false;
+    let mut specs = Vec::new();
+
+    for arg in args {
+        match arg.as_ref() {
+            "-l" => show_pids = true,
+            "-p" => only_pids = true,
+            _ => specs.push(arg),
+        }
+    }
+
+    #[cfg(unix)]
+    if let Err(why) = ctx.jobs.poll() {
+        io.eprintln(format!("Unable to update jobs! {}", why));
+    }
+
+    let ids: Vec<usize> = if specs.is_empty() {
+        ctx.jobs.jobs.iter().map(|job| job.id).collect()
+    } else {
+        let mut ids = Vec::new();
+        for spec in specs {
+            match ctx.jobs.find(Some(&spec)) {
+                Some(id) => ids.push(id),
+                None => {
+                    io.eprintln(format!("Unable to find job '{}'!", spec));
+
+                    return ExecOutcome::Status(1);
+                },
+            }
+        }
+
+        ids
+    };
+
+    for id in ids {
+        let job = match ctx.jobs.get(id) {
+            Some(job) => job,
+            None => continue,
+        };
+
+        let pids: Vec<String> = job.processes.iter().map(|process| process.pid.to_string()).collect();
+        if only_pids {
+            io.println(pids.join("\n"));
+        } else if show_pids {
+            io.println(format!("{} ({})", ctx.jobs.format(job), pids.join(" ")));
+        } else {
+            io.println(ctx.jobs.format(job));
+        }
+    }
+
+    // Finished jobs are only reported once
+    ctx.jobs.remove_done();
+
+    ExecOutcome::Status(0)
+};
@@ -0,0 +1,92 @@
+use std::path::Path;
+
+use br_data::{
+    command::{BrBuiltin, CommandIo, ExecOutcome, ExecuteFn, TabCompletionFn, TabCompletionType},
+    context::Context,
+    dirs::current_dir,
+    jump::{self, JumpDb},
+};
+
+use super::cd::change_dir;
+
+pub const CMD: BrBuiltin = BrBuiltin {
+    name: "jump",
+    tab_completion: TabCompletionType::Dynamic(tc_jump_list),
+    execute,
+};
+
+// Short name for 'jump'
+pub const Z_CMD: BrBuiltin = BrBuiltin {
+    name: "z",
+    ..CMD
+};
+
+// Matching directories ranked by frecency
+#[allow(non_upper_case_globals)]
+const tc_jump_list: TabCompletionFn = |args: Vec<String>, _ctx: &Context| -> Vec<String> {
+    let db = JumpDb::load();
+    let current = current_dir().to_string_lossy().to_string();
+
+    db.matches(&args)
+        .into_iter()
+        .filter(|entry| entry.path != current)
+        .map(|entry| entry.path.clone())
+        .collect()
+};
+
+#[allow(non_upper_case_globals)]
+const execute: ExecuteFn = |mut args: Vec<String>, ctx: &mut Context, io: &mut CommandIo| -> ExecOutcome {
+    let db = JumpDb::load();
+
+    // '-l' lists matches with their scores and '-r' forgets the
+    // current directory
+    match args.first().map(String::as_str) {
+        Some("-l") => {
+            args.remove(0);
+            return list_matches(&db, &args, io);
+        },
+        Some("-r") => {
+            if let Err(why) = jump::forget(&current_dir().to_string_lossy()) {
+                io.eprintln(format!("Unable to save directory history! {}", why));
+
+                return ExecOutcome::Status(1);
+            }
+
+            return ExecOutcome::Status(0);
+        },
+        _ => {},
+    }
+
+    if args.is_empty() {
+        return list_matches(&db, &args, io);
+    }
+
+    // Completion replaces the last term with a full path, which
+    // is used as is
+    if let Some(dir) = args.last().filter(|arg| Path::new(arg).is_absolute() && Path::new(arg).is_dir()) {
+        return change_dir(ctx, io, dir, false);
+    }
+
+    let current = current_dir().to_string_lossy().to_string();
+    let dir = match db.matches(&args).into_iter().find(|entry| entry.path != current) {
+        Some(entry) => entry.path.clone(),
+        None => {
+            io.eprintln(format!("No directory matching '{}'!", args.join(" ")));
+
+            return ExecOutcome::Status(1);
+        },
+    };
+
+    change_dir(ctx, io, &dir, false)
+};
+
+fn list_matches(db: &JumpDb, query: &[String], io: &mut CommandIo) -> ExecOutcome {
+    let now = jump::now();
+
+    // Best match last so it's closest to the prompt
+    for entry in db.matches(query).into_iter().rev() {
+        io.println(format!("{:>8.2}  {}", entry.score(now), entry.path));
+    }
+
+    ExecOutcome::Status(0)
+}
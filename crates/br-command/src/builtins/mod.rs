@@ -1,5 +1,27 @@
 pub mod alias;
+#[cfg(unix)]
+pub mod bg;
+pub mod bookmark;
 pub mod cd;
+pub mod command;
+pub mod dirs;
+pub mod disown;
+pub mod echo;
+pub mod exec;
 pub mod exit;
+pub mod export;
+#[cfg(unix)]
+pub mod fg;
 pub mod get;
+pub mod hash;
+pub mod hook;
+pub mod jobs;
+pub mod jump;
+pub mod popd;
+pub mod printf;
+pub mod pushd;
 pub mod set;
+pub mod trap;
+pub mod unset;
+#[cfg(unix)]
+pub mod wait;
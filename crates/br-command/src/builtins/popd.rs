@@ -0,0 +1,60 @@
+use br_data::{
+    command::{BrBuiltin, CommandIo, ExecOutcome, ExecuteFn, TabCompletionType},
+    context::Context,
+};
+
+use super::cd::move_to_dir;
+
+pub const CMD: BrBuiltin = BrBuiltin {
+    name: "popd",
+    tab_completion: TabCompletionType::None,
+    execute,
+};
+
+#[allow(non_upper_case_globals)]
+const execute: ExecuteFn = |args: Vec<String>, ctx: &mut Context, io: &mut CommandIo| -> ExecOutcome {
+    if args.len() > 1 {
+        io.eprintln(format!("Invalid arguments! Expected 0-1, got {}", args.len()));
+
+        return ExecOutcome::Status(1);
+    }
+
+    let mut stack: Vec<String> = ctx.dir_history.entries().cloned().collect();
+    if stack.is_empty() {
+        io.eprintln("Directory stack is empty!");
+
+        return ExecOutcome::Status(1);
+    }
+
+    // '+N' removes the Nth directory without changing directory,
+    // '+0' is the current directory
+    let idx = match args.first() {
+        Some(arg) => match arg.strip_prefix('+').and_then(|idx| idx.parse::<usize>().ok()) {
+            Some(idx) if idx <= stack.len() => idx,
+            _ => {
+                io.eprintln(format!("Invalid directory stack index '{}'!", arg));
+
+                return ExecOutcome::Status(1);
+            },
+        },
+        None => 0,
+    };
+
+    if idx > 0 {
+        stack.remove(idx - 1);
+        ctx.dir_history.replace(stack);
+        super::dirs::print_stack(ctx, io);
+
+        return ExecOutcome::Status(0);
+    }
+
+    let dir = stack.remove(0);
+    if let Err(output) = move_to_dir(ctx, io, &dir, false) {
+        return output;
+    }
+
+    ctx.dir_history.replace(stack);
+    super::dirs::print_stack(ctx, io);
+
+    ExecOutcome::Status(0)
+};
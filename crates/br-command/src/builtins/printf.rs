@@ -0,0 +1,563 @@
+use std::{iter::Peekable, str::Chars};
+
+use br_data::{
+    command::{BrBuiltin, CommandIo, ExecOutcome, ExecuteFn, TabCompletionType},
+    context::Context,
+};
+
+pub const CMD: BrBuiltin = BrBuiltin {
+    name: "printf",
+    tab_completion: TabCompletionType::None,
+    execute,
+};
+
+#[allow(non_upper_case_globals)]
+const execute: ExecuteFn = |mut args: Vec<String>, _ctx: &mut Context, io: &mut CommandIo| -> ExecOutcome {
+    if args.is_empty() {
+        io.eprintln("Invalid arguments! Expected at least 1, got 0");
+
+        return ExecOutcome::Status(1);
+    }
+
+    let format = args.remove(0);
+    let mut printer = Printer::new(args);
+    printer.run(&format);
+
+    io.print(&printer.output);
+    for error in &printer.errors {
+        io.eprintln(error);
+    }
+
+    if printer.errors.is_empty() {
+        ExecOutcome::Status(0)
+    } else {
+        ExecOutcome::Status(1)
+    }
+};
+
+// Replace escapes such as '\n' and '\x41', also returns whether a
+// '\c' was found, which stops all further output. echo and '%b'
+// write octal as '\0NNN' while formats use '\NNN'
+pub fn unescape(text: &str, zero_octal: bool) -> (String, bool) {
+    let mut output = String::new();
+    let mut chars = text.chars().peekable();
+    while let Some(ch) = chars.next() {
+        if ch != '\\' {
+            output.push(ch);
+            continue;
+        }
+
+        match chars.next() {
+            Some('a') => output.push('\x07'),
+            Some('b') => output.push('\x08'),
+            Some('c') => return (output, true),
+            Some('e') | Some('E') => output.push('\x1b'),
+            Some('f') => output.push('\x0c'),
+            Some('n') => output.push('\n'),
+            Some('r') => output.push('\r'),
+            Some('t') => output.push('\t'),
+            Some('v') => output.push('\x0b'),
+            Some('\\') => output.push('\\'),
+            Some('"') => output.push('"'),
+            Some('\'') => output.push('\''),
+            Some('x') => match take_digits(&mut chars, 16, 2) {
+                Some(code) => output.push(code),
+                None => output.push_str("\\x"),
+            },
+            Some('0') if zero_octal => output.push(take_digits(&mut chars, 8, 3).unwrap_or('\0')),
+            Some(digit @ '0'..='7') if !zero_octal => {
+                let mut code = digit.to_digit(8).unwrap_or_default();
+                for _ in 0..2 {
+                    match chars.peek().and_then(|ch| ch.to_digit(8)) {
+                        Some(digit) => {
+                            code = code * 8 + digit;
+                            chars.next();
+                        },
+                        None => break,
+                    }
+                }
+
+                output.push(char::from_u32(code).unwrap_or_default());
+            },
+            Some(ch) => {
+                output.push('\\');
+                output.push(ch);
+            },
+            None => output.push('\\'),
+        }
+    }
+
+    (output, false)
+}
+
+fn take_digits(chars: &mut Peekable<Chars>, radix: u32, max: usize) -> Option<char> {
+    let mut code = None;
+    for _ in 0..max {
+        match chars.peek().and_then(|ch| ch.to_digit(radix)) {
+            Some(digit) => {
+                code = Some(code.unwrap_or(0) * radix + digit);
+                chars.next();
+            },
+            None => break,
+        }
+    }
+
+    code.and_then(char::from_u32)
+}
+
+// Flags, width and precision of a single '%' conversion
+#[derive(Debug, Default)]
+struct Spec {
+    left:      bool,
+    zero:      bool,
+    plus:      bool,
+    space:     bool,
+    alt:       bool,
+    width:     usize,
+    precision: Option<usize>,
+}
+
+struct Printer {
+    args:   Vec<String>,
+    idx:    usize,
+    output: String,
+    errors: Vec<String>,
+    stop:   bool,
+}
+
+impl Printer {
+    fn new(args: Vec<String>) -> Self {
+        Self {
+            args,
+            idx: 0,
+            output: String::new(),
+            errors: Vec::new(),
+            stop: false,
+        }
+    }
+
+    // The format is reused until every argument has been used
+    fn run(&mut self, format: &str) {
+        loop {
+            let start = self.idx;
+            self.format_once(format);
+
+            if self.stop || self.idx >= self.args.len() || self.idx == start {
+                break;
+            }
+        }
+    }
+
+    fn next_arg(&mut self) -> String {
+        let arg = self.args.get(self.idx).cloned().unwrap_or_default();
+        self.idx += 1;
+
+        arg
+    }
+
+    fn format_once(&mut self, format: &str) {
+        let mut chars = format.chars().peekable();
+        while let Some(ch) = chars.next() {
+            match ch {
+                '\\' => {
+                    // Unescape one escape at a time so '\c' stops here
+                    let mut escape = String::from('\\');
+                    if let Some(next) = chars.next() {
+                        escape.push(next);
+                        // Up to two more hex or octal digits
+                        if next == 'x' || next.is_digit(8) {
+                            let radix = if next == 'x' { 16 } else { 8 };
+                            while escape.len() < 4 && chars.peek().is_some_and(|ch| ch.is_digit(radix)) {
+                                escape.push(chars.next().unwrap_or_default());
+                            }
+                        }
+                    }
+
+                    let (text, stop) = unescape(&escape, false);
+                    self.output.push_str(&text);
+                    if stop {
+                        self.stop = true;
+                        return;
+                    }
+                },
+                '%' => {
+                    if chars.peek() == Some(&'%') {
+                        chars.next();
+                        self.output.push('%');
+                        continue;
+                    }
+
+                    if !self.convert(&mut chars) {
+                        return;
+                    }
+                },
+                ch => self.output.push(ch),
+            }
+        }
+    }
+
+    // Format one conversion, false if output should stop
+    fn convert(&mut self, chars: &mut Peekable<Chars>) -> bool {
+        let mut spec = Spec::default();
+        while let Some(flag) = chars.peek() {
+            match flag {
+                '-' => spec.left = true,
+                '0' => spec.zero = true,
+                '+' => spec.plus = true,
+                ' ' => spec.space = true,
+                '#' => spec.alt = true,
+                _ => break,
+            }
+
+            chars.next();
+        }
+
+        spec.width = match self.take_number(chars) {
+            Some(width) if width < 0 => {
+                spec.left = true;
+                width.unsigned_abs() as usize
+            },
+            Some(width) => width as usize,
+            None => 0,
+        };
+
+        if chars.peek() == Some(&'.') {
+            chars.next();
+            spec.precision = Some(self.take_number(chars).unwrap_or(0).max(0) as usize);
+        }
+
+        let conversion = match chars.next() {
+            Some(conversion) => conversion,
+            None => {
+                self.errors.push(String::from("Missing format character!"));
+                self.stop = true;
+
+                return false;
+            },
+        };
+
+        let text = match conversion {
+            's' => {
+                let arg = self.next_arg();
+                match spec.precision {
+                    Some(precision) => arg.chars().take(precision).collect(),
+                    None => arg,
+                }
+            },
+            'b' => {
+                let (text, stop) = unescape(&self.next_arg(), true);
+                if stop {
+                    self.stop = true;
+                }
+
+                text
+            },
+            'q' => quote(&self.next_arg()),
+            'c' => self.next_arg().chars().next().map(String::from).unwrap_or_default(),
+            'd' | 'i' => {
+                let value = self.int_arg();
+                let digits = int_digits(value.unsigned_abs().to_string(), &spec);
+                self.push_number(&spec, sign(value < 0, &spec), "", digits, spec.precision.is_none());
+
+                return true;
+            },
+            'u' | 'o' | 'x' | 'X' => {
+                let value = self.int_arg() as u64;
+                let (digits, prefix) = match conversion {
+                    'o' => (format!("{:o}", value), ""),
+                    'x' => (format!("{:x}", value), if value != 0 && spec.alt { "0x" } else { "" }),
+                    'X' => (format!("{:X}", value), if value != 0 && spec.alt { "0X" } else { "" }),
+                    _ => (value.to_string(), ""),
+                };
+
+                let mut digits = int_digits(digits, &spec);
+                if conversion == 'o' && spec.alt && !digits.starts_with('0') {
+                    digits.insert(0, '0');
+                }
+
+                self.push_number(&spec, "", prefix, digits, spec.precision.is_none());
+
+                return true;
+            },
+            'f' | 'F' | 'e' | 'E' | 'g' | 'G' => {
+                let value = self.float_arg();
+                let digits = float_digits(value.abs(), conversion, &spec);
+                self.push_number(&spec, sign(value.is_sign_negative(), &spec), "", digits, value.is_finite());
+
+                return true;
+            },
+            _ => {
+                self.errors.push(format!("Invalid format character '%{}'!", conversion));
+                self.stop = true;
+
+                return false;
+            },
+        };
+
+        self.push_padded(&spec, &text);
+
+        !self.stop
+    }
+
+    // A width or precision, '*' takes it from the next argument
+    fn take_number(&mut self, chars: &mut Peekable<Chars>) -> Option<i64> {
+        if chars.peek() == Some(&'*') {
+            chars.next();
+            return Some(self.int_arg());
+        }
+
+        let mut number = None;
+        while let Some(digit) = chars.peek().and_then(|ch| ch.to_digit(10)) {
+            number = Some(number.unwrap_or(0) * 10 + digit as i64);
+            chars.next();
+        }
+
+        number
+    }
+
+    fn int_arg(&mut self) -> i64 {
+        let arg = self.next_arg();
+        match parse_int(&arg) {
+            Some(value) => value,
+            None => {
+                self.errors.push(format!("Invalid number '{}'!", arg));
+                0
+            },
+        }
+    }
+
+    fn float_arg(&mut self) -> f64 {
+        let arg = self.next_arg();
+        let trimmed = arg.trim();
+        if trimmed.is_empty() {
+            return 0.0;
+        }
+
+        match trimmed.parse::<f64>().ok().or_else(|| parse_int(trimmed).map(|value| value as f64)) {
+            Some(value) => value,
+            None => {
+                self.errors.push(format!("Invalid number '{}'!", arg));
+                0.0
+            },
+        }
+    }
+
+    fn push_padded(&mut self, spec: &Spec, text: &str) {
+        let padding = " ".repeat(spec.width.saturating_sub(text.chars().count()));
+        if spec.left {
+            self.output.push_str(text);
+            self.output.push_str(&padding);
+        } else {
+            self.output.push_str(&padding);
+            self.output.push_str(text);
+        }
+    }
+
+    // Numbers are zero padded between the sign and the digits
+    fn push_number(&mut self, spec: &Spec, sign: &str, prefix: &str, digits: String, zero_pad: bool) {
+        let len = sign.len() + prefix.len() + digits.len();
+        if spec.zero && !spec.left && zero_pad && len < spec.width {
+            let zeros = "0".repeat(spec.width - len);
+            self.output.push_str(&format!("{}{}{}{}", sign, prefix, zeros, digits));
+        } else {
+            self.push_padded(spec, &format!("{}{}{}", sign, prefix, digits));
+        }
+    }
+}
+
+// Integers can be decimal, hex with '0x', octal with a leading
+// '0' or a character code with a leading quote
+fn parse_int(arg: &str) -> Option<i64> {
+    let arg = arg.trim();
+    if arg.is_empty() {
+        return Some(0);
+    }
+
+    if let Some(ch) = arg.strip_prefix('\'').or_else(|| arg.strip_prefix('"')) {
+        return Some(ch.chars().next().map_or(0, |ch| ch as i64));
+    }
+
+    let (negative, number) = match arg.strip_prefix('-') {
+        Some(number) => (true, number),
+        None => (false, arg.strip_prefix('+').unwrap_or(arg)),
+    };
+
+    let value = if let Some(hex) = number.strip_prefix("0x").or_else(|| number.strip_prefix("0X")) {
+        i64::from_str_radix(hex, 16).ok()?
+    } else if number.len() > 1 && number.starts_with('0') {
+        i64::from_str_radix(&number[1..], 8).ok()?
+    } else {
+        number.parse::<i64>().ok()?
+    };
+
+    Some(if negative { -value } else { value })
+}
+
+fn sign(negative: bool, spec: &Spec) -> &'static str {
+    if negative {
+        "-"
+    } else if spec.plus {
+        "+"
+    } else if spec.space {
+        " "
+    } else {
+        ""
+    }
+}
+
+// Precision is the minimum number of digits for integers
+fn int_digits(digits: String, spec: &Spec) -> String {
+    match spec.precision {
+        Some(0) if digits == "0" => String::new(),
+        Some(precision) if digits.len() < precision => "0".repeat(precision - digits.len()) + &digits,
+        _ => digits,
+    }
+}
+
+fn float_digits(value: f64, conversion: char, spec: &Spec) -> String {
+    let upper = conversion.is_ascii_uppercase();
+    if !value.is_finite() {
+        let text = if value.is_nan() { "nan" } else { "inf" };
+        return if upper { text.to_uppercase() } else { text.to_string() };
+    }
+
+    let precision = spec.precision.unwrap_or(6);
+    let digits = match conversion.to_ascii_lowercase() {
+        'f' => format!("{:.*}", precision, value),
+        'e' => exp_digits(value, precision),
+        _ => {
+            // '%g' uses the shorter of '%f' and '%e' and drops
+            // trailing zeros
+            let precision = precision.max(1);
+            let exp = exponent(value, precision - 1);
+            let digits = if exp < -4 || exp >= precision as i32 {
+                exp_digits(value, precision - 1)
+            } else {
+                format!("{:.*}", (precision as i32 - 1 - exp).max(0) as usize, value)
+            };
+
+            if spec.alt {
+                digits
+            } else {
+                trim_zeros(&digits)
+            }
+        },
+    };
+
+    if upper {
+        digits.to_uppercase()
+    } else {
+        digits
+    }
+}
+
+// Exponent of a value after rounding to precision digits
+fn exponent(value: f64, precision: usize) -> i32 {
+    let digits = format!("{:.*e}", precision, value);
+    digits.split_once('e').and_then(|(_, exp)| exp.parse().ok()).unwrap_or(0)
+}
+
+// Same as C, at least two exponent digits with a sign
+fn exp_digits(value: f64, precision: usize) -> String {
+    let digits = format!("{:.*e}", precision, value);
+    match digits.split_once('e') {
+        Some((mantissa, exp)) => {
+            let exp: i32 = exp.parse().unwrap_or(0);
+            format!("{}e{}{:02}", mantissa, if exp < 0 { '-' } else { '+' }, exp.abs())
+        },
+        None => digits,
+    }
+}
+
+fn trim_zeros(digits: &str) -> String {
+    let (mantissa, exp) = match digits.find('e') {
+        Some(idx) => digits.split_at(idx),
+        None => (digits, ""),
+    };
+
+    if !mantissa.contains('.') {
+        return digits.to_string();
+    }
+
+    format!("{}{}", mantissa.trim_end_matches('0').trim_end_matches('.'), exp)
+}
+
+// Quote a value so it can be used as a single argument again,
+// string literals only unescape '\\' and '\"'
+fn quote(word: &str) -> String {
+    let is_plain = |ch: char| ch.is_ascii_alphanumeric() || "/_-:.%+?".contains(ch);
+    if !word.is_empty() && word.chars().all(is_plain) {
+        return word.to_string();
+    }
+
+    format!("\"{}\"", word.replace('\\', "\\\\").replace('"', "\\\""))
+}
+
+// Make cargo stop complaining about functions used for
+// tests
+#[allow(unused_imports, dead_code)]
+mod test {
+    use br_data::context::Context;
+    use br_parser::parse_command;
+
+    use super::{quote, unescape, Printer};
+
+    fn format(format: &str, args: &[&str]) -> String {
+        let mut printer = Printer::new(args.iter().map(|arg| arg.to_string()).collect());
+        printer.run(format);
+
+        printer.output
+    }
+
+    #[test]
+    fn conversions() {
+        assert_eq!(format("%s|%5s|%-3s|%.2s", &["a", "b", "c", "def"]), "a|    b|c  |de");
+        assert_eq!(format("%05d %+d %x %#o", &["-42", "7", "255", "8"]), "-0042 +7 ff 010");
+        assert_eq!(format("%.2f %e %g", &["3.14159", "1234.5", "0.0001"]), "3.14 1.234500e+03 0.0001");
+    }
+
+    #[test]
+    fn reuse_format() {
+        assert_eq!(format("%s=%s\\n", &["a", "1", "b"]), "a=1\nb=\n");
+    }
+
+    #[test]
+    fn escapes() {
+        assert_eq!(unescape("a\\tb\\0101\\x42", true), (String::from("a\tbAB"), false));
+        assert_eq!(unescape("a\\cb", true), (String::from("a"), true));
+    }
+
+    #[test]
+    fn quote_words() {
+        assert_eq!(quote("plain-word_1"), "plain-word_1");
+        assert_eq!(quote(""), "\"\"");
+        assert_eq!(quote("C:\\dir"), "\"C:\\\\dir\"");
+        assert_eq!(quote("say \"hi\""), "\"say \\\"hi\\\"\"");
+    }
+
+    #[test]
+    fn quote_round_trip() {
+        // Empty arguments are dropped by the parser so "" isn't
+        // checked here
+        let words = [
+            "plain",
+            "two words",
+            "C:\\dir",
+            "say \"hi\"",
+            "\\\"",
+            "tab\tnew\nline",
+            "~/$HOME",
+            "@mark",
+            "a|b;c",
+        ];
+
+        let ctx = Context::default();
+        for word in words {
+            let command = format!("printf {}", quote(word));
+            let parsed = parse_command(command.clone(), &ctx).unwrap_or_default();
+
+            assert_eq!(parsed.len(), 1, "{}", command);
+            assert_eq!(parsed[0].args, vec![word.to_string()], "{}", command);
+        }
+    }
+}
@@ -0,0 +1,78 @@
+use br_data::{
+    command::{BrBuiltin, CommandIo, ExecOutcome, ExecuteFn, TabCompletionType},
+    context::Context,
+    dirs::current_dir,
+};
+
+use super::cd::{change_dir, move_to_dir};
+
+pub const CMD: BrBuiltin = BrBuiltin {
+    name: "pushd",
+    tab_completion: TabCompletionType::Directory(Vec::new()),
+    execute,
+};
+
+#[allow(non_upper_case_globals)]
+const execute: ExecuteFn = |args: Vec<String>, ctx: &mut Context, io: &mut CommandIo| -> ExecOutcome {
+    if args.len() > 1 {
+        io.eprintln(format!("Invalid arguments! Expected 0-1, got {}", args.len()));
+
+        return ExecOutcome::Status(1);
+    }
+
+    let current = current_dir().to_string_lossy().to_string();
+    let mut stack: Vec<String> = ctx.dir_history.entries().cloned().collect();
+
+    // No directory swaps the top two directories and '+N'
+    // rotates the stack so the Nth directory is on top
+    let dir = match args.first() {
+        None => {
+            if stack.is_empty() {
+                io.eprintln("Directory stack is empty!");
+
+                return ExecOutcome::Status(1);
+            }
+
+            let dir = stack.remove(0);
+            stack.insert(0, current);
+
+            dir
+        },
+        Some(arg) if arg.starts_with('+') && arg.len() > 1 => {
+            let mut entries = vec![current];
+            entries.extend(stack);
+
+            let idx = match arg[1..].parse::<usize>() {
+                Ok(idx) if idx < entries.len() => idx,
+                _ => {
+                    io.eprintln(format!("Invalid directory stack index '{}'!", arg));
+
+                    return ExecOutcome::Status(1);
+                },
+            };
+
+            entries.rotate_left(idx);
+            stack = entries.split_off(1);
+
+            entries.remove(0)
+        },
+        // Moving to a new directory pushes the current one
+        Some(dir) => {
+            let output = change_dir(ctx, io, dir, false);
+            if output.is_success() {
+                super::dirs::print_stack(ctx, io);
+            }
+
+            return output;
+        },
+    };
+
+    if let Err(output) = move_to_dir(ctx, io, &dir, false) {
+        return output;
+    }
+
+    ctx.dir_history.replace(stack);
+    super::dirs::print_stack(ctx, io);
+
+    ExecOutcome::Status(0)
+};
@@ -1,26 +1,74 @@
+use std::collections::BTreeMap;
+
 use br_data::{
-    command::{BrBuiltin, ExecuteFn, TabCompletionType},
+    command::{BrBuiltin, CommandIo, ExecOutcome, ExecuteFn, TabCompletionFn, TabCompletionType},
     context::Context,
+    setting::find_setting,
+    variable::VarValue,
 };
 
 use super::get::tc_var_list;
 
 pub const CMD: BrBuiltin = BrBuiltin {
     name: "set",
-    tab_completion: TabCompletionType::Dynamic(tc_var_list),
+    tab_completion: TabCompletionType::Dynamic(tc_set),
     execute,
 };
 
+// Complete variable names, then valid values for settings
 #[allow(non_upper_case_globals)]
-const execute: ExecuteFn = |args: Vec<String>, ctx: &mut Context| -> i32 {
+const tc_set: TabCompletionFn = |args: Vec<String>, ctx: &Context| -> Vec<String> {
     if args.len() != 2 {
-        println!("Invalid arguments! Expected 2, got {}", args.len());
+        return tc_var_list(args, ctx);
+    }
+
+    match find_setting(&args[0]) {
+        Some(setting) => setting.values().into_iter().filter(|value| value.starts_with(&args[1])).collect(),
+        None => Vec::new(),
+    }
+};
+
+// Short flag, long name and the variable storing each option
+const OPTIONS: &[(Option<char>, &str, &str)] = &[
+    (Some('e'), "errexit", "OPT_ERREXIT"),
+    (Some('u'), "nounset", "OPT_NOUNSET"),
+    (Some('x'), "xtrace", "OPT_XTRACE"),
+    (None, "pipefail", "OPT_PIPEFAIL"),
+];
+
+#[allow(non_upper_case_globals)]
+const execute: ExecuteFn = |mut args: Vec<String>, ctx: &mut Context, io: &mut CommandIo| -> ExecOutcome {
+    // '-l' sets a list, '-m' a map and '-a' appends to either,
+    // '-r' makes the variable read only and '-U' universal
+    let mut list_flag = None;
+    let mut readonly = false;
+    let mut universal = false;
+    while let Some(arg) = args.first() {
+        match arg.as_str() {
+            "-l" | "-m" | "-a" => list_flag = Some(arg.clone()),
+            "-r" | "--readonly" => readonly = true,
+            "-U" | "--universal" => universal = true,
+            _ => break,
+        }
 
-        return 1;
+        args.remove(0);
     }
 
-    let mut var_name = args[0].clone();
-    let var_value = args[1].clone();
+    if list_flag.is_none() && !readonly && !universal {
+        if let Some(arg) = args.first() {
+            if arg.starts_with('-') || arg.starts_with('+') {
+                return set_options(args, ctx, io);
+            }
+        }
+    }
+
+    if args.len() < 2 && !((list_flag.is_some() || readonly) && args.len() == 1) {
+        io.eprintln(format!("Invalid arguments! Expected at least 2, got {}", args.len()));
+
+        return ExecOutcome::Status(1);
+    }
+
+    let mut var_name = args.remove(0);
     let is_env = if var_name.starts_with("ENV:") {
         let (_, var_name_) = var_name.split_at(4);
         var_name = var_name_.to_string();
@@ -36,12 +84,164 @@ const execute: ExecuteFn = |args: Vec<String>, ctx: &mut Context| -> i32 {
         .position(|ch| !((ch.is_alphanumeric() || ch == '_') && ch != ' '))
     {
         let invalid_char = var_name.chars().nth(pos).unwrap_or_default();
-        println!("Invalid character at position {}, '{}'", pos, invalid_char);
+        io.eprintln(format!("Invalid character at position {}, '{}'", pos, invalid_char));
+
+        return ExecOutcome::Status(1);
+    }
+
+    if is_env && (readonly || universal) {
+        io.eprintln("Environment variables can't be read only or universal!");
+
+        return ExecOutcome::Status(1);
+    }
+
+    if !is_env && ctx.is_readonly(&var_name) {
+        io.eprintln(format!("Unable to set '{}'! Variable is read only", var_name));
+
+        return ExecOutcome::Status(1);
+    }
+
+    // 'set -r NAME' makes an existing variable read only
+    if args.is_empty() && list_flag.is_none() {
+        let var = match ctx.variables.get_mut(&var_name) {
+            Some(var) => var,
+            None => {
+                io.eprintln(format!("Unable to find variable '{}'!", var_name));
+
+                return ExecOutcome::Status(1);
+            },
+        };
+
+        if !var.universal {
+            var.readonly = true;
+
+            return ExecOutcome::Status(0);
+        }
+
+        let value = var.value.clone();
+        if let Err(why) = ctx.set_universal(&var_name, value, true) {
+            io.eprintln(format!("Unable to save universal variable! {}", why));
+
+            return ExecOutcome::Status(1);
+        }
+
+        return ExecOutcome::Status(0);
+    }
 
-        return 1;
+    if is_env {
+        if list_flag.is_some() || args.len() > 1 {
+            io.eprintln("Environment variables can only be strings!");
+
+            return ExecOutcome::Status(1);
+        }
+
+        ctx.set_variable(&var_name, args.remove(0), true);
+
+        return ExecOutcome::Status(0);
+    }
+
+    let value = match list_flag.as_deref() {
+        None if args.len() == 1 => VarValue::Str(args.remove(0)),
+        Some("-m") => match to_map(args, io) {
+            Some(map) => VarValue::Map(map),
+            None => return ExecOutcome::Status(1),
+        },
+        Some("-a") => match ctx.get_variable_value(&var_name, false) {
+            Some(VarValue::Map(mut map)) => match to_map(args, io) {
+                Some(pairs) => {
+                    map.extend(pairs);
+                    VarValue::Map(map)
+                },
+                None => return ExecOutcome::Status(1),
+            },
+            Some(value) => VarValue::List(value.items().into_iter().chain(args).collect()),
+            None => VarValue::List(args),
+        },
+        _ => VarValue::List(args),
+    };
+
+    if let Err(why) = ctx.validate_variable(&var_name, &value) {
+        io.eprintln(why);
+
+        return ExecOutcome::Status(1);
     }
 
-    ctx.set_variable(&var_name, var_value, is_env);
+    // Universal variables stay universal when they're changed
+    let universal = universal || ctx.variables.get(&var_name).is_some_and(|var| var.universal);
+    if universal {
+        if let Err(why) = ctx.set_universal(&var_name, value, readonly) {
+            io.eprintln(format!("Unable to save universal variable! {}", why));
 
-    0
+            return ExecOutcome::Status(1);
+        }
+    } else {
+        ctx.set_variable_value(&var_name, value);
+    }
+
+    if readonly {
+        if let Some(var) = ctx.variables.get_mut(&var_name) {
+            var.readonly = true;
+        }
+    }
+
+    ExecOutcome::Status(0)
 };
+
+fn to_map(args: Vec<String>, io: &mut CommandIo) -> Option<BTreeMap<String, String>> {
+    if !args.len().is_multiple_of(2) {
+        io.eprintln("Invalid arguments! Expected pairs of keys and values");
+
+        return None;
+    }
+
+    Some(args.chunks(2).map(|pair| (pair[0].clone(), pair[1].clone())).collect())
+}
+
+// Handle 'set -e', 'set +x', 'set -o pipefail' and friends
+fn set_options(args: Vec<String>, ctx: &mut Context, io: &mut CommandIo) -> ExecOutcome {
+    let mut args = args.into_iter();
+    while let Some(arg) = args.next() {
+        let enable = arg.starts_with('-');
+        let flags = &arg[1..];
+
+        if flags == "o" {
+            let name = match args.next() {
+                Some(name) => name,
+                None => return list_options(ctx, io),
+            };
+
+            match OPTIONS.iter().find(|(_, long, _)| *long == name) {
+                Some((_, _, var_name)) => ctx.set_variable(var_name, enable, false),
+                None => {
+                    io.eprintln(format!("Invalid option '{}'!", name));
+
+                    return ExecOutcome::Status(1);
+                },
+            }
+
+            continue;
+        }
+
+        for flag in flags.chars() {
+            match OPTIONS.iter().find(|(short, ..)| *short == Some(flag)) {
+                Some((_, _, var_name)) => ctx.set_variable(var_name, enable, false),
+                None => {
+                    io.eprintln(format!("Invalid option '{}{}'!", &arg[..1], flag));
+
+                    return ExecOutcome::Status(1);
+                },
+            }
+        }
+    }
+
+    ExecOutcome::Status(0)
+}
+
+fn list_options(ctx: &mut Context, io: &mut CommandIo) -> ExecOutcome {
+    for (_, name, var_name) in OPTIONS {
+        let state = if ctx.get_variable(var_name, false, false) { "on" } else { "off" };
+        io.println(format!("{:<10} {}", name, state));
+    }
+
+    ExecOutcome::Status(0)
+}
@@ -0,0 +1,106 @@
+use br_data::{
+    command::{BrBuiltin, CommandIo, ExecOutcome, ExecuteFn, TabCompletionFn, TabCompletionType},
+    context::Context,
+    signal,
+};
+
+pub const CMD: BrBuiltin = BrBuiltin {
+    name: "trap",
+    tab_completion: TabCompletionType::Dynamic(tc_signal_list),
+    execute,
+};
+
+#[allow(non_upper_case_globals)]
+const tc_signal_list: TabCompletionFn = |args: Vec<String>, _ctx: &Context| -> Vec<String> {
+    if args.len() <= 1 {
+        return Vec::new();
+    }
+
+    let cur_arg = args.last().cloned().unwrap_or_default().to_uppercase();
+    signal::SIGNALS
+        .iter()
+        .map(|(name, _)| name.to_string())
+        .filter(|name| name.starts_with(&cur_arg))
+        .collect()
+};
+
+#[allow(non_upper_case_globals)]
+const execute: ExecuteFn = |mut args: Vec<String>, ctx: &mut Context, io: &mut CommandIo| -> ExecOutcome {
+    if args.is_empty() {
+        return list_traps(ctx, io);
+    }
+
+    if args[0] == "-l" {
+        for (name, sig) in signal::SIGNALS.iter().filter(|(_, sig)| *sig != signal::EXIT) {
+            io.println(format!("{:>2}) {}", sig, name));
+        }
+
+        return ExecOutcome::Status(0);
+    }
+
+    // A single signal resets it, same as '-'
+    let handler = if args.len() == 1 || args[0] == "-" {
+        if args[0] == "-" {
+            args.remove(0);
+        }
+
+        None
+    } else {
+        Some(args.remove(0))
+    };
+
+    let mut output = ExecOutcome::Status(0);
+    for name in args {
+        let sig = match signal::from_name(&name) {
+            Some(sig) => sig,
+            None => {
+                io.eprintln(format!("Invalid signal '{}'!", name));
+                output = ExecOutcome::Status(1);
+
+                continue;
+            },
+        };
+
+        if let Err(why) = set_trap(ctx, sig, handler.clone()) {
+            io.eprintln(why);
+            output = ExecOutcome::Status(1);
+        }
+    }
+
+    output
+};
+
+fn set_trap(ctx: &mut Context, sig: i32, handler: Option<String>) -> Result<(), String> {
+    #[cfg(unix)]
+    {
+        if sig == libc::SIGKILL || sig == libc::SIGSTOP {
+            return Err(format!("Unable to trap signal '{}'!", signal::name(sig)));
+        }
+
+        if sig != signal::EXIT {
+            match &handler {
+                Some(handler) if handler.is_empty() => signal::ignore(sig),
+                Some(_) => signal::catch(sig),
+                None => signal::reset(sig),
+            }
+        }
+    }
+
+    match handler {
+        Some(handler) => ctx.traps.insert(sig, handler),
+        None => ctx.traps.remove(&sig),
+    };
+
+    Ok(())
+}
+
+fn list_traps(ctx: &mut Context, io: &mut CommandIo) -> ExecOutcome {
+    let mut traps: Vec<(&i32, &String)> = ctx.traps.iter().collect();
+    traps.sort();
+
+    for (sig, handler) in traps {
+        io.println(format!("trap -- \"{}\" {}", handler.replace('"', "\\\""), signal::name(*sig)));
+    }
+
+    ExecOutcome::Status(0)
+}
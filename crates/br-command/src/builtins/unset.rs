@@ -0,0 +1,53 @@
+use br_data::{
+    command::{BrBuiltin, CommandIo, ExecOutcome, ExecuteFn, TabCompletionType},
+    context::Context,
+};
+
+use super::get::tc_var_list;
+
+pub const CMD: BrBuiltin = BrBuiltin {
+    name: "unset",
+    tab_completion: TabCompletionType::Dynamic(tc_var_list),
+    execute,
+};
+
+#[allow(non_upper_case_globals)]
+const execute: ExecuteFn = |args: Vec<String>, ctx: &mut Context, io: &mut CommandIo| -> ExecOutcome {
+    if args.is_empty() {
+        io.eprintln("Invalid arguments! Expected at least 1, got 0");
+
+        return ExecOutcome::Status(1);
+    }
+
+    let mut output = ExecOutcome::Status(0);
+    for arg in args {
+        let (var_name, is_env) = match arg.strip_prefix("ENV:") {
+            Some(var_name) => (var_name, true),
+            None => (arg.as_str(), false),
+        };
+
+        if !is_env && ctx.is_readonly(var_name) {
+            io.eprintln(format!("Unable to unset '{}'! Variable is read only", var_name));
+            output = ExecOutcome::Status(1);
+
+            continue;
+        }
+
+        // Universal variables are removed from every session
+        if !is_env && ctx.variables.get(var_name).is_some_and(|var| var.universal) {
+            if let Err(why) = ctx.unset_universal(var_name) {
+                io.eprintln(format!("Unable to remove universal variable! {}", why));
+                output = ExecOutcome::Status(1);
+            }
+
+            continue;
+        }
+
+        if !ctx.unset_variable(var_name, is_env) {
+            io.eprintln(format!("Unable to find variable '{}'!", arg));
+            output = ExecOutcome::Status(1);
+        }
+    }
+
+    output
+};
@@ -0,0 +1,64 @@
+use br_data::{
+    command::{BrBuiltin, CommandIo, ExecOutcome, ExecuteFn, TabCompletionType},
+    context::Context,
+    job,
+};
+
+use super::jobs::tc_job_list;
+
+pub const CMD: BrBuiltin = BrBuiltin {
+    name: "wait",
+    tab_completion: TabCompletionType::Dynamic(tc_job_list),
+    execute,
+};
+
+#[allow(non_upper_case_globals)]
+const execute: ExecuteFn = |args: Vec<String>, ctx: &mut Context, io: &mut CommandIo| -> ExecOutcome {
+    let ids: Vec<usize> = if args.is_empty() {
+        ctx.jobs.jobs.iter().map(|job| job.id).collect()
+    } else {
+        let mut ids = Vec::new();
+        for arg in args {
+            // Accept either a job spec or the pid of a process
+            let id = if arg.starts_with('%') {
+                ctx.jobs.find(Some(&arg))
+            } else {
+                arg.parse::<i32>().ok().and_then(|pid| {
+                    ctx.jobs
+                        .jobs
+                        .iter()
+                        .find(|job| job.processes.iter().any(|process| process.pid == pid))
+                        .map(|job| job.id)
+                })
+            };
+
+            match id {
+                Some(id) => ids.push(id),
+                None => {
+                    io.eprintln(format!("Unable to find job '{}'!", arg));
+
+                    return ExecOutcome::Status(127);
+                },
+            }
+        }
+
+        ids
+    };
+
+    let mut output = ExecOutcome::Status(0);
+    for id in ids {
+        if let Some(job) = ctx.jobs.get_mut(id) {
+            if let Err(why) = job::wait_job(job) {
+                io.eprintln(format!("Unable to wait for job! {}", why));
+
+                return ExecOutcome::Status(1);
+            }
+
+            output = job.outcome();
+        }
+    }
+
+    ctx.jobs.remove_done();
+
+    output
+};
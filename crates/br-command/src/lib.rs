@@ -10,11 +10,31 @@ use br_data::{command::TabCompletionType, context::Context};
 pub fn load_builtins(ctx: &mut Context) {
     ctx.builtins = vec![
         builtins::alias::CMD.clone(),
+        builtins::bookmark::CMD.clone(),
         builtins::cd::CMD,
+        builtins::command::CMD,
+        builtins::dirs::CMD.clone(),
+        builtins::disown::CMD,
+        builtins::echo::CMD,
+        builtins::exec::CMD,
         builtins::exit::CMD,
+        builtins::export::CMD,
         builtins::get::CMD,
+        builtins::hash::CMD,
+        builtins::hook::CMD.clone(),
+        builtins::jobs::CMD.clone(),
+        builtins::jump::CMD,
+        builtins::jump::Z_CMD,
+        builtins::popd::CMD,
+        builtins::printf::CMD,
+        builtins::pushd::CMD,
         builtins::set::CMD,
+        builtins::trap::CMD,
+        builtins::unset::CMD,
     ];
+
+    #[cfg(unix)]
+    ctx.builtins.extend(vec![builtins::bg::CMD, builtins::fg::CMD, builtins::wait::CMD]);
 }
 
 pub fn get_tab_completion(cmd: String, args: Vec<String>, ctx: &mut Context) -> Vec<String> {
@@ -25,7 +45,7 @@ pub fn get_tab_completion(cmd: String, args: Vec<String>, ctx: &mut Context) ->
     }
 
     let arg = args.into_iter().last().unwrap_or_default();
-    get_file(FileType::Both, arg)
+    get_file(FileType::Both, arg, ctx)
 }
 
 #[derive(Debug, PartialEq)]
@@ -57,7 +77,7 @@ fn get_comp(tc_type: TabCompletionType, args: Vec<String>, ctx: &mut Context) ->
 
             // If is last arg
             if itr.peek().is_none() {
-                return get_file(FileType::from(&tc_type), arg);
+                return get_file(FileType::from(&tc_type), arg, ctx);
             } else {
                 for sub in subargs {
                     if sub.arg == arg {
@@ -96,19 +116,37 @@ fn get_comp(tc_type: TabCompletionType, args: Vec<String>, ctx: &mut Context) ->
     }
 }
 
-fn get_file(file_type: FileType, mut arg: String) -> Vec<String> {
-    let mut trim_home_dir = None;
-    if arg.starts_with('~') { 
-        if arg.starts_with("~/") || arg == "~" {
-            let home_dir = match home::home_dir() {
-                Some(home_dir) => home_dir.to_string_lossy().to_string(),
-                None => String::from("~")
-            };
-
-            trim_home_dir = Some(home_dir.clone());
-            arg = home_dir + arg.strip_prefix("~").unwrap()
-        } else {
-            // TODO: Get other user home dir
+fn get_file(file_type: FileType, mut arg: String, ctx: &Context) -> Vec<String> {
+    // The expanded start of the path and how it's shown
+    let mut trim_prefix = None;
+    if arg.starts_with("~/") || arg == "~" {
+        let home_dir = match home::home_dir() {
+            Some(home_dir) => home_dir.to_string_lossy().to_string(),
+            None => String::from("~")
+        };
+
+        trim_prefix = Some((home_dir.clone(), String::from("~")));
+        arg = home_dir + arg.strip_prefix("~").unwrap()
+    } else if arg.starts_with('~') || arg.starts_with('@') {
+        let (prefix, mark) = arg.split_at(1);
+
+        // Complete bookmark names until a '/' is typed
+        let name = match mark.split_once('/') {
+            Some((name, _)) => name.to_string(),
+            None => {
+                return ctx
+                    .bookmarks
+                    .marks
+                    .keys()
+                    .filter(|name| name.starts_with(mark))
+                    .map(|name| format!("{}{}", prefix, name))
+                    .collect();
+            },
+        };
+
+        if let (Some(expanded), Some(dir)) = (ctx.bookmarks.expand(&arg), ctx.bookmarks.marks.get(&name)) {
+            trim_prefix = Some((dir.clone(), format!("{}{}", prefix, name)));
+            arg = expanded;
         }
     }
 
@@ -120,7 +158,7 @@ fn get_file(file_type: FileType, mut arg: String) -> Vec<String> {
         (".", arg.as_str())
     };
 
-    let path = PathBuf::from_str(&cur_path).unwrap();
+    let path = PathBuf::from_str(cur_path).unwrap();
     let children_wrapped = path.read_dir();
     if !path.exists() || children_wrapped.is_err() {
         return Vec::new();
@@ -145,8 +183,8 @@ fn get_file(file_type: FileType, mut arg: String) -> Vec<String> {
             .starts_with(cur_entry))
         .map(|child| {
             let mut path = cur_path.to_string();
-            if let Some(home) = trim_home_dir.clone() {
-                path = path.replace(&home, "~");
+            if let Some((expanded, shown)) = &trim_prefix {
+                path = path.replacen(expanded.as_str(), shown, 1);
             }
 
             if print_cur_path {
@@ -0,0 +1,111 @@
+use std::{
+    collections::BTreeMap,
+    fs,
+    io,
+    path::{Path, PathBuf},
+};
+
+use crate::{get_config_dir, write_atomic};
+
+// Named directories, used as '@name' or '~name' at the start of a
+// path and saved in the config dir
+#[derive(Debug, Default, Clone)]
+pub struct Bookmarks {
+    path:      Option<PathBuf>,
+    pub marks: BTreeMap<String, String>,
+}
+
+impl Bookmarks {
+    pub fn load() -> Self {
+        let path = get_config_dir().map(|dir| Path::new(&dir).join("bookmarks"));
+        let marks = match path.as_ref().and_then(|path| fs::read_to_string(path).ok()) {
+            Some(contents) => contents
+                .lines()
+                .filter_map(|line| line.split_once('\t'))
+                .map(|(name, dir)| (name.to_string(), dir.to_string()))
+                .collect(),
+            None => BTreeMap::new(),
+        };
+
+        Self {
+            path,
+            marks,
+        }
+    }
+
+    pub fn save(&self) -> io::Result<()> {
+        let path = match &self.path {
+            Some(path) => path,
+            None => return Err(io::Error::new(io::ErrorKind::NotFound, "No config directory")),
+        };
+
+        let contents: String = self.marks.iter().map(|(name, dir)| format!("{}\t{}\n", name, dir)).collect();
+
+        write_atomic(path, &contents)
+    }
+
+    // Expand a leading '@name' or '~name', None if the word
+    // doesn't start with a bookmark
+    pub fn expand(&self, word: &str) -> Option<String> {
+        let word = word.strip_prefix('@').or_else(|| word.strip_prefix('~'))?;
+        let (name, rest) = match word.find('/') {
+            Some(idx) => word.split_at(idx),
+            None => (word, ""),
+        };
+
+        self.marks.get(name).map(|dir| format!("{}{}", dir, rest))
+    }
+
+    // Replace the longest bookmarked directory containing dir
+    // with '@name'
+    pub fn shorten(&self, dir: &str) -> Option<String> {
+        self.marks
+            .iter()
+            .filter(|(_, mark)| dir == mark.as_str() || dir.starts_with(&format!("{}/", mark)))
+            .max_by_key(|(_, mark)| mark.len())
+            .map(|(name, mark)| format!("@{}{}", name, &dir[mark.len()..]))
+    }
+}
+
+// Make cargo stop complaining about functions used for
+// tests
+#[allow(unused_imports, dead_code)]
+mod test {
+    use super::Bookmarks;
+
+    fn bookmarks() -> Bookmarks {
+        let mut bookmarks = Bookmarks::default();
+        bookmarks.marks.insert(String::from("proj"), String::from("/work/proj"));
+        bookmarks.marks.insert(String::from("src"), String::from("/work/proj/src"));
+        bookmarks.marks.insert(String::from("root"), String::from("/"));
+
+        bookmarks
+    }
+
+    #[test]
+    fn expand() {
+        let bookmarks = bookmarks();
+
+        assert_eq!(bookmarks.expand("@proj"), Some(String::from("/work/proj")));
+        assert_eq!(bookmarks.expand("~proj/a/b"), Some(String::from("/work/proj/a/b")));
+        assert_eq!(bookmarks.expand("@proj/"), Some(String::from("/work/proj/")));
+        assert_eq!(bookmarks.expand("@missing/a"), None);
+        assert_eq!(bookmarks.expand("proj"), None);
+        assert_eq!(bookmarks.expand("~/proj"), None);
+        assert_eq!(bookmarks.expand("@"), None);
+    }
+
+    #[test]
+    fn shorten() {
+        let bookmarks = bookmarks();
+
+        assert_eq!(bookmarks.shorten("/work/proj"), Some(String::from("@proj")));
+        assert_eq!(bookmarks.shorten("/work/proj/docs"), Some(String::from("@proj/docs")));
+        // The longest bookmark is used
+        assert_eq!(bookmarks.shorten("/work/proj/src/lib"), Some(String::from("@src/lib")));
+        // Only whole components match
+        assert_eq!(bookmarks.shorten("/work/project"), None);
+        assert_eq!(bookmarks.shorten("/"), Some(String::from("@root")));
+        assert_eq!(bookmarks.shorten("/etc"), None);
+    }
+}
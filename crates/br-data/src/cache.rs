@@ -0,0 +1,137 @@
+#[cfg(unix)]
+use std::os::unix::prelude::MetadataExt;
+use std::{
+    collections::HashMap,
+    env,
+    ffi::OsString,
+    fs::Metadata,
+    path::{Path, PathBuf},
+    time::SystemTime,
+};
+
+#[cfg(unix)]
+pub fn can_exec(md: Metadata) -> bool {
+    let mode = md.mode();
+    mode & 0o001 == 0o001
+}
+
+#[cfg(windows)]
+pub fn can_exec(md: Metadata) -> bool {
+    // TODO: Windows perms
+    true
+}
+
+#[derive(Debug, Clone)]
+pub struct CachedCommand {
+    pub path: PathBuf,
+    pub hits: usize,
+}
+
+// Executables found on PATH, rebuilt when PATH changes or one of
+// its directories is modified
+#[derive(Debug, Default, Clone)]
+pub struct CommandCache {
+    path:     Option<OsString>,
+    dirs:     Vec<(PathBuf, Option<SystemTime>)>,
+    commands: HashMap<String, CachedCommand>,
+    built:    bool,
+}
+
+impl CommandCache {
+    pub fn clear(&mut self) {
+        *self = Self::default();
+    }
+
+    pub fn lookup(&mut self, name: &str) -> Option<PathBuf> {
+        self.refresh();
+        self.commands.get(name).map(|command| command.path.clone())
+    }
+
+    // Same as lookup but counts towards the hits shown by 'hash'
+    pub fn lookup_hit(&mut self, name: &str) -> Option<PathBuf> {
+        self.refresh();
+
+        let command = self.commands.get_mut(name)?;
+        command.hits += 1;
+
+        Some(command.path.clone())
+    }
+
+    pub fn names(&mut self) -> Vec<String> {
+        self.refresh();
+        self.commands.keys().cloned().collect()
+    }
+
+    // Commands that have been run at least once, sorted by name
+    pub fn used(&self) -> Vec<(String, CachedCommand)> {
+        let mut used: Vec<(String, CachedCommand)> = self
+            .commands
+            .iter()
+            .filter(|(_, command)| command.hits > 0)
+            .map(|(name, command)| (name.clone(), command.clone()))
+            .collect();
+        used.sort_by(|a, b| a.0.cmp(&b.0));
+
+        used
+    }
+
+    fn refresh(&mut self) {
+        if !self.is_stale() {
+            return;
+        }
+
+        let hits: HashMap<String, usize> = self
+            .commands
+            .drain()
+            .map(|(name, command)| (name, command.hits))
+            .collect();
+
+        self.path = env::var_os("PATH");
+        self.dirs = match &self.path {
+            Some(paths) => env::split_paths(paths).map(|dir| (dir.clone(), modified(&dir))).collect(),
+            None => Vec::new(),
+        };
+        self.built = true;
+
+        for (dir, _) in &self.dirs {
+            let files = match dir.read_dir() {
+                Ok(files) => files,
+                Err(_) => continue,
+            };
+
+            for file in files.flatten() {
+                let name = match file.file_name().to_str() {
+                    Some(name) => name.to_string(),
+                    None => continue,
+                };
+
+                // Earlier directories on PATH take priority
+                if self.commands.contains_key(&name) {
+                    continue;
+                }
+
+                if let Ok(metadata) = file.metadata() {
+                    if can_exec(metadata) {
+                        let hits = hits.get(&name).copied().unwrap_or(0);
+                        self.commands.insert(name, CachedCommand {
+                            path: file.path(),
+                            hits,
+                        });
+                    }
+                }
+            }
+        }
+    }
+
+    fn is_stale(&self) -> bool {
+        if !self.built || env::var_os("PATH") != self.path {
+            return true;
+        }
+
+        self.dirs.iter().any(|(dir, mtime)| modified(dir) != *mtime)
+    }
+}
+
+fn modified(dir: &Path) -> Option<SystemTime> {
+    dir.metadata().and_then(|metadata| metadata.modified()).ok()
+}
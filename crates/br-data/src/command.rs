@@ -1,10 +1,98 @@
-use std::fmt;
+use std::{
+    fmt,
+    io::{stderr, stdin, stdout, Read, Write},
+};
 
 use crate::context::Context;
 
-pub type ExecuteFn = fn(args: Vec<String>, ctx: &mut Context) -> i32;
+pub type ExecuteFn = fn(args: Vec<String>, ctx: &mut Context, io: &mut CommandIo) -> ExecOutcome;
 pub type TabCompletionFn = fn(Vec<String>, &Context) -> Vec<String>;
 
+#[derive(Debug, PartialEq, Clone, Copy, Eq, Hash)]
+pub enum ExecOutcome {
+    // Finished with the given exit status
+    Status(i32),
+    // Terminated by the given signal
+    Signaled(i32),
+    // Shell exit requested with the given code
+    Exit(i32),
+    Break,
+    Continue,
+    Return(i32),
+    // Input was unable to be parsed
+    ParseError,
+}
+
+impl Default for ExecOutcome {
+    fn default() -> Self {
+        Self::Status(0)
+    }
+}
+
+impl ExecOutcome {
+    pub fn code(&self) -> i32 {
+        match self {
+            Self::Status(code) | Self::Exit(code) | Self::Return(code) => *code,
+            Self::Signaled(signal) => 128 + signal,
+            Self::Break | Self::Continue => 0,
+            Self::ParseError => 2,
+        }
+    }
+
+    pub fn is_success(&self) -> bool {
+        self.code() == 0
+    }
+
+    // Whether the outcome should stop execution of the
+    // current script or command list
+    pub fn is_control_flow(&self) -> bool {
+        matches!(self, Self::Exit(_) | Self::Break | Self::Continue | Self::Return(_))
+    }
+}
+
+impl From<i32> for ExecOutcome {
+    fn from(code: i32) -> Self {
+        Self::Status(code)
+    }
+}
+
+pub struct CommandIo {
+    pub stdin:  Box<dyn Read>,
+    pub stdout: Box<dyn Write>,
+    pub stderr: Box<dyn Write>,
+}
+
+impl Default for CommandIo {
+    fn default() -> Self {
+        Self {
+            stdin:  Box::new(stdin()),
+            stdout: Box::new(stdout()),
+            stderr: Box::new(stderr()),
+        }
+    }
+}
+
+impl CommandIo {
+    // Write errors are ignored as there's nowhere left to
+    // report them, the same as when using println!
+    pub fn print<T: fmt::Display>(&mut self, text: T) {
+        let _ = write!(self.stdout, "{}", text);
+    }
+
+    pub fn println<T: fmt::Display>(&mut self, text: T) {
+        let _ = writeln!(self.stdout, "{}", text);
+    }
+
+    pub fn eprintln<T: fmt::Display>(&mut self, text: T) {
+        let _ = writeln!(self.stderr, "{}", text);
+    }
+
+    pub fn flush(&mut self) {
+        let _ = self.stdout.flush();
+        let _ = self.stderr.flush();
+    }
+}
+
 #[derive(Clone)]
 pub enum TabCompletionType {
     None,
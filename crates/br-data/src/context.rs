@@ -1,13 +1,24 @@
 use std::{
-    collections::HashMap,
+    cell::RefCell,
+    collections::{BTreeMap, HashMap},
     env,
-    io::{stdout, Stdout},
+    io::{self, stdout, Stdout},
     str::FromStr,
 };
 
 use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
 
-use crate::command::BrBuiltin;
+use crate::{
+    bookmark::Bookmarks,
+    cache::CommandCache,
+    command::BrBuiltin,
+    dirs::DirHistory,
+    hook::HookEvent,
+    job::JobTable,
+    setting::{find_setting, SETTINGS},
+    universal::UniversalStore,
+    variable::{VarValue, Variable},
+};
 
 #[derive(Debug, Default, Clone)]
 pub struct CommandBufferBackup {
@@ -74,30 +85,55 @@ impl CliContext {
 }
 
 pub struct Context {
-    pub cli:       CliContext,
-    pub writer:    Stdout,
-    pub variables: HashMap<String, String>,
-    pub aliases:   HashMap<String, String>,
-    pub builtins:  Vec<BrBuiltin>,
+    pub cli:         CliContext,
+    pub writer:      Stdout,
+    pub variables:   HashMap<String, Variable>,
+    pub aliases:     HashMap<String, String>,
+    pub builtins:    Vec<BrBuiltin>,
+    pub last_status: i32,
+    pub jobs:        JobTable,
+    pub job_control: bool,
+    pub traps:       HashMap<i32, String>,
+    pub hooks:       HashMap<HookEvent, Vec<String>>,
+    // Interior mutability so lookups can be cached while
+    // highlighting, which only has a shared reference
+    pub commands:    RefCell<CommandCache>,
+    pub universal:   UniversalStore,
+    pub dir_history: DirHistory,
+    pub bookmarks:   Bookmarks,
+    // Set while the command_not_found hook runs so it doesn't
+    // call itself if it also can't be found
+    pub in_not_found_hook: bool,
+    // Scripts currently being sourced, so a script that sources
+    // itself can't recurse forever
+    pub source_depth: usize,
 }
 
 impl Default for Context {
     fn default() -> Self {
-        let mut vars = HashMap::new();
-
-        vars.insert(String::from("PROMPT"), String::from("{WD} | "));
-        vars.insert(String::from("P_HOME_TRUNC"), String::from("true"));
-        vars.insert(String::from("P_HOME_CHAR"), String::from("~"));
-        vars.insert(String::from("P_DIR_TRUNC"), String::from("2"));
-        vars.insert(String::from("P_DIR_CHAR"), String::from("…"));
-        vars.insert(String::from("SYN_HIGHLIGHTING"), String::from("true"));
-
         Self {
-            cli:       CliContext::default(),
-            writer:    stdout(),
-            variables: vars,
-            aliases:   HashMap::new(),
-            builtins:  Vec::new(),
+            cli:         CliContext::default(),
+            writer:      stdout(),
+            variables:   SETTINGS
+                .iter()
+                .map(|setting| {
+                    let value = VarValue::Str(setting.default.to_string());
+                    (setting.name.to_string(), Variable::new(value))
+                })
+                .collect(),
+            aliases:     HashMap::new(),
+            builtins:    Vec::new(),
+            last_status: 0,
+            jobs:        JobTable::default(),
+            job_control: false,
+            traps:       HashMap::new(),
+            hooks:       HashMap::new(),
+            commands:    RefCell::new(CommandCache::default()),
+            universal:   UniversalStore::new(),
+            dir_history: DirHistory::default(),
+            bookmarks:   Bookmarks::default(),
+            in_not_found_hook: false,
+            source_depth: 0,
         }
     }
 }
@@ -110,8 +146,10 @@ impl Context {
             } else {
                 return default;
             }
-        } else if let Some(val) = self.variables.get(var_name) {
-            val.clone()
+        } else if var_name == "?" {
+            self.last_status.to_string()
+        } else if let Some(var) = self.variables.get(var_name) {
+            var.value.to_string()
         } else {
             return default;
         };
@@ -123,11 +161,168 @@ impl Context {
         default
     }
 
+    pub fn has_variable(&self, var_name: &str, env: bool) -> bool {
+        if env {
+            env::var_os(var_name).is_some()
+        } else {
+            var_name == "?" || self.variables.contains_key(var_name)
+        }
+    }
+
+    // Get the full value of a variable, environment variables
+    // are always strings
+    pub fn get_variable_value(&self, var_name: &str, env: bool) -> Option<VarValue> {
+        if env {
+            env::var(var_name).ok().map(VarValue::Str)
+        } else if var_name == "?" {
+            Some(VarValue::Str(self.last_status.to_string()))
+        } else {
+            self.variables.get(var_name).map(|var| var.value.clone())
+        }
+    }
+
     pub fn set_variable<T: ToString>(&mut self, var_name: &str, var_value: T, is_env: bool) {
         if is_env {
             env::set_var(var_name, var_value.to_string());
         } else {
-            self.variables.insert(var_name.to_string(), var_value.to_string());
+            self.set_variable_value(var_name, VarValue::Str(var_value.to_string()));
         }
     }
+
+    pub fn set_variable_value(&mut self, var_name: &str, var_value: VarValue) {
+        // Keep the exported flag of existing variables
+        let var = self.variables.entry(var_name.to_string()).or_default();
+        var.value = var_value;
+    }
+
+    // Returns false if the variable doesn't exist
+    pub fn unset_variable(&mut self, var_name: &str, is_env: bool) -> bool {
+        if is_env {
+            let exists = env::var_os(var_name).is_some();
+            env::remove_var(var_name);
+
+            exists
+        } else {
+            self.remove_variable(var_name)
+        }
+    }
+
+    // Settings can't be removed so they're reset to their default
+    fn remove_variable(&mut self, var_name: &str) -> bool {
+        match find_setting(var_name) {
+            Some(setting) => {
+                let value = VarValue::Str(setting.default.to_string());
+                self.variables.insert(var_name.to_string(), Variable::new(value));
+
+                true
+            },
+            None => self.variables.remove(var_name).is_some(),
+        }
+    }
+
+    // Settings must be a string of the right type, checked before
+    // setting a variable from user input
+    pub fn validate_variable(&self, var_name: &str, var_value: &VarValue) -> Result<(), String> {
+        let setting = match find_setting(var_name) {
+            Some(setting) => setting,
+            None => return Ok(()),
+        };
+
+        match var_value {
+            VarValue::Str(value) => setting.validate(value),
+            _ => Err(format!("{} can't be a list or map!", setting.name)),
+        }
+    }
+
+    pub fn is_readonly(&self, var_name: &str) -> bool {
+        self.variables.get(var_name).is_some_and(|var| var.readonly)
+    }
+
+    // Reload universal variables if another session changed them
+    pub fn sync_universal(&mut self) {
+        if !self.universal.is_stale() {
+            return;
+        }
+
+        let universal = self.universal.load();
+        self.apply_universal(universal);
+    }
+
+    // Replace the universal variables with the ones in the file,
+    // read only variables in this session are never changed by
+    // other sessions
+    fn apply_universal(&mut self, universal: BTreeMap<String, Variable>) {
+        let removed: Vec<String> = self
+            .variables
+            .iter()
+            .filter(|(name, var)| var.universal && !var.readonly && !universal.contains_key(*name))
+            .map(|(name, _)| name.clone())
+            .collect();
+
+        for name in removed {
+            self.remove_variable(&name);
+        }
+
+        for (name, universal_var) in universal {
+            // Skip invalid settings written by hand or by older
+            // versions, keeping the current value
+            if self.is_readonly(&name) || self.validate_variable(&name, &universal_var.value).is_err() {
+                continue;
+            }
+
+            let var = self.variables.entry(name).or_default();
+            var.value = universal_var.value;
+            var.readonly = universal_var.readonly;
+            var.universal = true;
+        }
+    }
+
+    // The file is locked from loading until it's saved, changes
+    // made by other sessions in the meantime are applied too
+    pub fn set_universal(&mut self, var_name: &str, var_value: VarValue, readonly: bool) -> io::Result<()> {
+        let _lock = self.universal.lock()?;
+        let mut universal = self.universal.load();
+        self.apply_universal(universal.clone());
+
+        if universal.get(var_name).is_some_and(|var| var.readonly) {
+            return Err(io::Error::new(io::ErrorKind::PermissionDenied, "Variable is read only"));
+        }
+
+        let mut universal_var = Variable::new(var_value.clone());
+        universal_var.readonly = readonly;
+        universal_var.universal = true;
+        universal.insert(var_name.to_string(), universal_var);
+        self.universal.save(&universal)?;
+
+        let var = self.variables.entry(var_name.to_string()).or_default();
+        var.value = var_value;
+        var.readonly = readonly;
+        var.universal = true;
+
+        Ok(())
+    }
+
+    pub fn unset_universal(&mut self, var_name: &str) -> io::Result<()> {
+        let _lock = self.universal.lock()?;
+        let mut universal = self.universal.load();
+        self.apply_universal(universal.clone());
+
+        if universal.remove(var_name).is_some() {
+            self.universal.save(&universal)?;
+        }
+
+        self.remove_variable(var_name);
+
+        Ok(())
+    }
+
+    // Shell variables passed to child processes along with the
+    // shell's own environment
+    pub fn exported_variables(&self) -> Vec<(String, String)> {
+        self.variables
+            .iter()
+            .filter(|(_, var)| var.exported)
+            .map(|(name, var)| (name.clone(), var.export_value()))
+            .collect()
+    }
 }
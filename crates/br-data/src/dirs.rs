@@ -0,0 +1,274 @@
+use std::{
+    env,
+    fs,
+    path::{Component, Path, PathBuf},
+};
+
+use crate::context::Context;
+
+// Directories visited with 'cd', 'cd -N' moves back through them
+// and 'cd +N' forward again. The directories behind are also the
+// stack used by 'pushd', 'popd' and 'dirs'
+#[derive(Debug, Default, Clone)]
+pub struct DirHistory {
+    back:    Vec<String>,
+    forward: Vec<String>,
+}
+
+impl DirHistory {
+    // Record the directory being left, clearing anything ahead
+    pub fn push(&mut self, dir: String) {
+        self.back.push(dir);
+        self.forward.clear();
+    }
+
+    pub fn peek_back(&self, count: usize) -> Option<&String> {
+        peek(&self.back, count)
+    }
+
+    pub fn peek_forward(&self, count: usize) -> Option<&String> {
+        peek(&self.forward, count)
+    }
+
+    // Move back count entries, call after the directory has been
+    // changed to peek_back(count)
+    pub fn move_back(&mut self, count: usize, current: String) {
+        shift(&mut self.back, &mut self.forward, count, current);
+    }
+
+    pub fn move_forward(&mut self, count: usize, current: String) {
+        shift(&mut self.forward, &mut self.back, count, current);
+    }
+
+    // Previous directories, most recent first
+    pub fn entries(&self) -> impl Iterator<Item = &String> {
+        self.back.iter().rev()
+    }
+
+    // Replace the previous directories, most recent first, for
+    // 'pushd' and 'popd' which reorder them. Anything ahead is
+    // dropped
+    pub fn replace(&mut self, entries: Vec<String>) {
+        self.back = entries.into_iter().rev().collect();
+        self.forward.clear();
+    }
+}
+
+fn peek(dirs: &[String], count: usize) -> Option<&String> {
+    if count == 0 || count > dirs.len() {
+        return None;
+    }
+
+    dirs.get(dirs.len() - count)
+}
+
+// Move count entries from one side of the history to the other,
+// the furthest is the new directory so it's dropped instead
+fn shift(from: &mut Vec<String>, to: &mut Vec<String>, count: usize, current: String) {
+    if count == 0 || count > from.len() {
+        return;
+    }
+
+    let mut passed = from.split_off(from.len() - count);
+    passed.remove(0);
+
+    to.push(current);
+    to.extend(passed.into_iter().rev());
+}
+
+// Working directory with symlinks kept, taken from PWD when it
+// still points at the current directory
+pub fn current_dir() -> PathBuf {
+    let physical = env::current_dir().unwrap_or_default();
+
+    if let Some(pwd) = env::var_os("PWD").map(PathBuf::from) {
+        if pwd.is_absolute() && fs::canonicalize(&pwd).ok() == fs::canonicalize(&physical).ok() {
+            return pwd;
+        }
+    }
+
+    physical
+}
+
+// Resolve '.' and '..' without following symlinks
+pub fn normalize(path: &Path) -> PathBuf {
+    let mut normalized = PathBuf::new();
+    for component in path.components() {
+        match component {
+            Component::CurDir => {},
+            Component::ParentDir => {
+                normalized.pop();
+            },
+            component => normalized.push(component),
+        }
+    }
+
+    normalized
+}
+
+pub fn expand_home(dir: &str) -> Option<String> {
+    match dir.strip_prefix('~') {
+        Some(rest) => {
+            let home_dir = home::home_dir()?;
+            Some(format!("{}{}", home_dir.to_str()?, rest))
+        },
+        None => Some(dir.to_string()),
+    }
+}
+
+// Relative directories not starting with '.' or '..' that don't
+// exist in the current directory are searched for in CDPATH,
+// either a list or ':' separated
+pub fn search_cdpath(ctx: &Context, dir: &str) -> Option<PathBuf> {
+    let path = Path::new(dir);
+    match path.components().next() {
+        Some(Component::Normal(_)) if !path.is_dir() => {},
+        _ => return None,
+    }
+
+    let cdpath = match ctx.get_variable_value("CDPATH", false) {
+        Some(value) => value.items(),
+        None => env::var("CDPATH").into_iter().collect(),
+    };
+
+    cdpath
+        .iter()
+        .flat_map(env::split_paths)
+        .filter(|base| !base.as_os_str().is_empty())
+        .map(|base| base.join(path))
+        .find(|candidate| candidate.is_dir())
+}
+
+// Directory a command name moves to when AUTO_CD is set, callers
+// check the name isn't a command first
+pub fn auto_cd_dir(ctx: &Context, word: &str) -> Option<PathBuf> {
+    if !ctx.get_variable("AUTO_CD", false, false) {
+        return None;
+    }
+
+    let dir = expand_home(word)?;
+    if Path::new(&dir).is_dir() {
+        return Some(PathBuf::from(dir));
+    }
+
+    search_cdpath(ctx, &dir)
+}
+
+// Make cargo stop complaining about functions used for
+// tests
+#[allow(unused_imports, dead_code)]
+mod test {
+    use std::path::{Path, PathBuf};
+
+    use super::{normalize, DirHistory};
+
+    // History after visiting /a, /b, /c and then /d
+    fn history() -> DirHistory {
+        let mut history = DirHistory::default();
+        for dir in ["/a", "/b", "/c"] {
+            history.push(dir.to_string());
+        }
+
+        history
+    }
+
+    // Same as 'cd -N' or 'cd +N', returns the new directory
+    fn go(history: &mut DirHistory, current: &str, count: isize) -> Option<String> {
+        let dir = if count < 0 {
+            history.peek_back(count.unsigned_abs())?.clone()
+        } else {
+            history.peek_forward(count as usize)?.clone()
+        };
+
+        if count < 0 {
+            history.move_back(count.unsigned_abs(), current.to_string());
+        } else {
+            history.move_forward(count as usize, current.to_string());
+        }
+
+        Some(dir)
+    }
+
+    #[test]
+    fn peek() {
+        let history = history();
+
+        assert_eq!(history.peek_back(1).map(String::as_str), Some("/c"));
+        assert_eq!(history.peek_back(3).map(String::as_str), Some("/a"));
+        assert_eq!(history.peek_back(4), None);
+        assert_eq!(history.peek_back(0), None);
+        assert_eq!(history.peek_forward(1), None);
+    }
+
+    #[test]
+    fn back_and_forward() {
+        let mut history = history();
+
+        assert_eq!(go(&mut history, "/d", -1).as_deref(), Some("/c"));
+        assert_eq!(go(&mut history, "/c", -1).as_deref(), Some("/b"));
+        assert_eq!(go(&mut history, "/b", 1).as_deref(), Some("/c"));
+        assert_eq!(go(&mut history, "/c", 1).as_deref(), Some("/d"));
+        assert_eq!(go(&mut history, "/d", 1), None);
+
+        let entries: Vec<&String> = history.entries().collect();
+        assert_eq!(entries, vec!["/c", "/b", "/a"]);
+    }
+
+    #[test]
+    fn jump_several() {
+        let mut history = history();
+
+        assert_eq!(go(&mut history, "/d", -3).as_deref(), Some("/a"));
+        assert_eq!(history.entries().count(), 0);
+        assert_eq!(history.peek_forward(1).map(String::as_str), Some("/b"));
+        assert_eq!(history.peek_forward(3).map(String::as_str), Some("/d"));
+
+        assert_eq!(go(&mut history, "/a", 2).as_deref(), Some("/c"));
+        assert_eq!(history.peek_back(1).map(String::as_str), Some("/b"));
+        assert_eq!(history.peek_back(2).map(String::as_str), Some("/a"));
+        assert_eq!(history.peek_forward(1).map(String::as_str), Some("/d"));
+        assert_eq!(history.peek_forward(2), None);
+    }
+
+    #[test]
+    fn push_clears_forward() {
+        let mut history = history();
+        go(&mut history, "/d", -2);
+        history.push(String::from("/b"));
+
+        assert_eq!(history.peek_forward(1), None);
+        assert_eq!(history.peek_back(1).map(String::as_str), Some("/b"));
+    }
+
+    #[test]
+    fn out_of_range() {
+        let mut history = history();
+        history.move_back(4, String::from("/d"));
+        history.move_forward(1, String::from("/d"));
+
+        assert_eq!(history.entries().count(), 3);
+        assert_eq!(history.peek_forward(1), None);
+    }
+
+    #[test]
+    fn replace_entries() {
+        let mut history = history();
+        go(&mut history, "/d", -1);
+        history.replace(vec![String::from("/x"), String::from("/y")]);
+
+        let entries: Vec<&String> = history.entries().collect();
+        assert_eq!(entries, vec!["/x", "/y"]);
+        assert_eq!(history.peek_back(2).map(String::as_str), Some("/y"));
+        assert_eq!(history.peek_forward(1), None);
+    }
+
+    #[test]
+    fn normalize_paths() {
+        assert_eq!(normalize(Path::new("/a/./b/../c")), PathBuf::from("/a/c"));
+        assert_eq!(normalize(Path::new("/a/b/../../..")), PathBuf::from("/"));
+        assert_eq!(normalize(Path::new("/..")), PathBuf::from("/"));
+        assert_eq!(normalize(Path::new("/../a/.")), PathBuf::from("/a"));
+        assert_eq!(normalize(Path::new("/./.")), PathBuf::from("/"));
+        assert_eq!(normalize(Path::new("/a//b/")), PathBuf::from("/a/b"));
+    }
+}
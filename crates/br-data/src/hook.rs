@@ -0,0 +1,28 @@
+#[derive(Debug, PartialEq, Clone, Copy, Eq, Hash)]
+pub enum HookEvent {
+    // Before a command line is executed, gets the line
+    Preexec,
+    // Before each prompt is drawn
+    Precmd,
+    // After the working directory changes
+    Chpwd,
+    // When the shell exits
+    OnExit,
+}
+
+impl HookEvent {
+    pub const ALL: [Self; 4] = [Self::Preexec, Self::Precmd, Self::Chpwd, Self::OnExit];
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            Self::Preexec => "preexec",
+            Self::Precmd => "precmd",
+            Self::Chpwd => "chpwd",
+            Self::OnExit => "on_exit",
+        }
+    }
+
+    pub fn from_name(name: &str) -> Option<Self> {
+        Self::ALL.iter().find(|event| event.name() == name).copied()
+    }
+}
@@ -0,0 +1,512 @@
+#[cfg(unix)]
+use std::{io, os::unix::process::ExitStatusExt, process::ExitStatus};
+
+use crate::command::ExecOutcome;
+#[cfg(unix)]
+use crate::signal;
+
+#[derive(Debug, PartialEq, Clone, Copy, Eq, Hash)]
+pub enum JobState {
+    Running,
+    Stopped,
+    Done,
+}
+
+impl JobState {
+    pub fn name(&self) -> &'static str {
+        match self {
+            Self::Running => "Running",
+            Self::Stopped => "Stopped",
+            Self::Done => "Done",
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct JobProcess {
+    pub pid:     i32,
+    pub stopped: bool,
+    pub outcome: Option<ExecOutcome>,
+}
+
+impl JobProcess {
+    pub const fn new(pid: i32) -> Self {
+        Self {
+            pid,
+            stopped: false,
+            outcome: None,
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct Job {
+    pub id:        usize,
+    pub pgid:      i32,
+    pub command:   String,
+    pub processes: Vec<JobProcess>,
+}
+
+impl Job {
+    pub fn new(pgid: i32, command: String, pids: Vec<i32>) -> Self {
+        Self {
+            id: 0,
+            pgid,
+            command,
+            processes: pids.into_iter().map(JobProcess::new).collect(),
+        }
+    }
+
+    pub fn state(&self) -> JobState {
+        if self.processes.iter().all(|process| process.outcome.is_some()) {
+            JobState::Done
+        } else if self.processes.iter().any(|process| process.stopped) {
+            JobState::Stopped
+        } else {
+            JobState::Running
+        }
+    }
+
+    // State shown by 'jobs', includes the exit code of
+    // failed jobs
+    pub fn describe(&self) -> String {
+        match (self.state(), self.outcome()) {
+            (JobState::Done, ExecOutcome::Status(code)) if code != 0 => format!("Exit {}", code),
+            (JobState::Done, ExecOutcome::Signaled(signal)) => format!("Signal {}", signal),
+            (state, _) => state.name().to_string(),
+        }
+    }
+
+    // Outcome of the last process in the pipeline
+    pub fn outcome(&self) -> ExecOutcome {
+        self.processes
+            .last()
+            .and_then(|process| process.outcome)
+            .unwrap_or_default()
+    }
+
+    #[cfg(unix)]
+    pub fn update(&mut self, pid: i32, status: i32) {
+        if let Some(process) = self.processes.iter_mut().find(|process| process.pid == pid) {
+            if libc::WIFSTOPPED(status) {
+                process.stopped = true;
+            } else if libc::WIFCONTINUED(status) {
+                process.stopped = false;
+            } else {
+                process.stopped = false;
+                process.outcome = Some(get_outcome(ExitStatus::from_raw(status)));
+            }
+        }
+    }
+}
+
+#[derive(Debug, Default)]
+pub struct JobTable {
+    pub jobs:     Vec<Job>,
+    pub current:  Option<usize>,
+    pub previous: Option<usize>,
+}
+
+impl JobTable {
+    pub fn add(&mut self, mut job: Job) -> usize {
+        let id = self.jobs.iter().map(|job| job.id).max().unwrap_or(0) + 1;
+        job.id = id;
+        self.jobs.push(job);
+        self.set_current(id);
+
+        id
+    }
+
+    pub fn get(&self, id: usize) -> Option<&Job> {
+        self.jobs.iter().find(|job| job.id == id)
+    }
+
+    pub fn get_mut(&mut self, id: usize) -> Option<&mut Job> {
+        self.jobs.iter_mut().find(|job| job.id == id)
+    }
+
+    pub fn remove(&mut self, id: usize) -> Option<Job> {
+        let idx = self.jobs.iter().position(|job| job.id == id)?;
+        let job = self.jobs.remove(idx);
+
+        if self.current == Some(id) {
+            self.current = self.previous.take();
+        } else if self.previous == Some(id) {
+            self.previous = None;
+        }
+
+        if self.current.is_none() {
+            self.current = self.jobs.last().map(|job| job.id);
+        }
+
+        Some(job)
+    }
+
+    pub fn set_current(&mut self, id: usize) {
+        if self.current != Some(id) {
+            self.previous = self.current;
+            self.current = Some(id);
+        }
+    }
+
+    // Get job id from a job spec such as '%1', '%+', '%-' or
+    // '%name', no spec refers to the current job
+    pub fn find(&self, spec: Option<&str>) -> Option<usize> {
+        let spec = match spec {
+            Some(spec) => spec.strip_prefix('%').unwrap_or(spec),
+            None => return self.current,
+        };
+
+        match spec {
+            "" | "%" | "+" => self.current,
+            "-" => self.previous,
+            _ => {
+                if let Ok(id) = spec.parse::<usize>() {
+                    return self.get(id).map(|job| job.id);
+                }
+
+                if let Some(search) = spec.strip_prefix('?') {
+                    return self.jobs.iter().rev().find(|job| job.command.contains(search)).map(|job| job.id);
+                }
+
+                self.jobs
+                    .iter()
+                    .rev()
+                    .find(|job| job.command.starts_with(spec))
+                    .map(|job| job.id)
+            },
+        }
+    }
+
+    pub fn marker(&self, id: usize) -> char {
+        if self.current == Some(id) {
+            '+'
+        } else if self.previous == Some(id) {
+            '-'
+        } else {
+            ' '
+        }
+    }
+
+    // Update the state of every job without blocking
+    #[cfg(unix)]
+    pub fn poll(&mut self) -> io::Result<()> {
+        let mut result = Ok(());
+        for job in self.jobs.iter_mut() {
+            for process in job.processes.clone() {
+                if process.outcome.is_some() {
+                    continue;
+                }
+
+                let mut status = 0;
+                let options = libc::WNOHANG | libc::WUNTRACED | libc::WCONTINUED;
+                match unsafe { libc::waitpid(process.pid, &mut status, options) } {
+                    0 => {},
+                    -1 => {
+                        let why = io::Error::last_os_error();
+                        if why.raw_os_error() != Some(libc::ECHILD) {
+                            result = Err(why);
+                            continue;
+                        }
+
+                        // Reaped elsewhere so treat as finished
+                        if let Some(process) = job.processes.iter_mut().find(|p| p.pid == process.pid) {
+                            process.outcome = Some(ExecOutcome::Status(0));
+                        }
+                    },
+                    pid => job.update(pid, status),
+                }
+            }
+        }
+
+        result
+    }
+
+    // Remove jobs that have finished, returning them
+    pub fn remove_done(&mut self) -> Vec<Job> {
+        let done: Vec<usize> = self
+            .jobs
+            .iter()
+            .filter(|job| job.state() == JobState::Done)
+            .map(|job| job.id)
+            .collect();
+
+        done.into_iter().filter_map(|id| self.remove(id)).collect()
+    }
+
+    pub fn format(&self, job: &Job) -> String {
+        format!(
+            "[{}]{}  {:<10} {}",
+            job.id,
+            self.marker(job.id),
+            job.describe(),
+            job.command
+        )
+    }
+}
+
+#[cfg(unix)]
+pub fn get_outcome(exit_status: ExitStatus) -> ExecOutcome {
+    match exit_status.code() {
+        Some(code) => ExecOutcome::Status(code),
+        None => match exit_status.signal() {
+            Some(signal) => ExecOutcome::Signaled(signal),
+            None => ExecOutcome::Status(0),
+        },
+    }
+}
+
+// Put the shell into its own process group and take control
+// of the terminal, returns whether job control is available
+#[cfg(unix)]
+pub fn init_job_control() -> bool {
+    unsafe {
+        if libc::isatty(libc::STDIN_FILENO) == 0 {
+            return false;
+        }
+
+        // Stop the shell from being suspended when changing
+        // the foreground process group
+        libc::signal(libc::SIGTSTP, libc::SIG_IGN);
+        libc::signal(libc::SIGTTIN, libc::SIG_IGN);
+        libc::signal(libc::SIGTTOU, libc::SIG_IGN);
+
+        let pid = libc::getpid();
+        if libc::getpgrp() != pid {
+            // Fails if already a session leader which is fine
+            libc::setpgid(0, pid);
+        }
+
+        libc::tcsetpgrp(libc::STDIN_FILENO, libc::getpgrp()) == 0
+    }
+}
+
+#[cfg(unix)]
+pub fn give_terminal(pgid: i32) {
+    unsafe {
+        libc::tcsetpgrp(libc::STDIN_FILENO, pgid);
+    }
+}
+
+#[cfg(unix)]
+pub fn take_terminal() {
+    unsafe {
+        libc::tcsetpgrp(libc::STDIN_FILENO, libc::getpgrp());
+    }
+}
+
+// Restore the signals ignored by the shell, called in child
+// processes before running a command
+#[cfg(unix)]
+pub fn reset_signals() {
+    let signals = [
+        libc::SIGINT,
+        libc::SIGQUIT,
+        libc::SIGHUP,
+        libc::SIGTERM,
+        libc::SIGTSTP,
+        libc::SIGTTIN,
+        libc::SIGTTOU,
+    ];
+
+    unsafe {
+        for signal in &signals {
+            libc::signal(*signal, libc::SIG_DFL);
+        }
+    }
+}
+
+// Send a signal to every process in the job
+#[cfg(unix)]
+pub fn signal_job(job: &Job, signal: i32) {
+    unsafe {
+        if has_own_group(job) {
+            libc::killpg(job.pgid, signal);
+            return;
+        }
+
+        for process in job.processes.iter().filter(|process| process.outcome.is_none()) {
+            libc::kill(process.pid, signal);
+        }
+    }
+}
+
+#[cfg(unix)]
+fn has_own_group(job: &Job) -> bool {
+    unsafe { libc::getpgid(job.pgid) == job.pgid && job.pgid != libc::getpgrp() }
+}
+
+// Pass signals sent to the shell on to the job being waited on,
+// terminal signals only need forwarding when the job has its
+// own process group as otherwise it already received them
+#[cfg(unix)]
+fn forward_signals(job: &Job, forwarded: &mut Vec<i32>) {
+    for sig in signal::pending() {
+        if forwarded.contains(&sig) {
+            continue;
+        }
+
+        let forward = match sig {
+            libc::SIGHUP | libc::SIGTERM => true,
+            libc::SIGINT | libc::SIGQUIT => has_own_group(job),
+            _ => false,
+        };
+
+        if forward {
+            signal_job(job, sig);
+            forwarded.push(sig);
+        }
+    }
+}
+
+// Block until every process in the job has exited or one of
+// them has been stopped
+#[cfg(unix)]
+pub fn wait_job(job: &mut Job) -> io::Result<()> {
+    let mut forwarded = Vec::new();
+    for idx in 0..job.processes.len() {
+        while job.processes[idx].outcome.is_none() && !job.processes[idx].stopped {
+            let pid = job.processes[idx].pid;
+
+            let mut status = 0;
+            if unsafe { libc::waitpid(pid, &mut status, libc::WUNTRACED) } == -1 {
+                let why = io::Error::last_os_error();
+                match why.raw_os_error() {
+                    Some(libc::EINTR) => {
+                        forward_signals(job, &mut forwarded);
+                        continue;
+                    },
+                    Some(libc::ECHILD) => {
+                        // Already reaped elsewhere so treat as finished
+                        job.processes[idx].outcome = Some(ExecOutcome::Status(0));
+                        break;
+                    },
+                    _ => return Err(why),
+                }
+            }
+
+            job.update(pid, status);
+        }
+
+        if job.state() == JobState::Stopped {
+            break;
+        }
+    }
+
+    // Collect any other stopped processes so they aren't
+    // reported later on
+    if job.state() == JobState::Stopped {
+        for process in job.processes.clone() {
+            if process.outcome.is_some() || process.stopped {
+                continue;
+            }
+
+            let mut status = 0;
+            if unsafe { libc::waitpid(process.pid, &mut status, libc::WUNTRACED | libc::WNOHANG) } > 0 {
+                job.update(process.pid, status);
+            }
+        }
+    }
+
+    Ok(())
+}
+
+#[cfg(unix)]
+pub fn continue_job(job: &mut Job) -> io::Result<()> {
+    for process in job.processes.iter_mut() {
+        process.stopped = false;
+    }
+
+    // Without job control the processes share the shell's
+    // group so each one is continued on its own
+    if has_own_group(job) {
+        if unsafe { libc::killpg(job.pgid, libc::SIGCONT) } == -1 {
+            return Err(io::Error::last_os_error());
+        }
+
+        return Ok(());
+    }
+
+    for process in job.processes.iter().filter(|process| process.outcome.is_none()) {
+        if unsafe { libc::kill(process.pid, libc::SIGCONT) } == -1 {
+            return Err(io::Error::last_os_error());
+        }
+    }
+
+    Ok(())
+}
+
+// Run a job in the foreground, handing over the terminal while
+// it runs if job control is enabled
+#[cfg(unix)]
+pub fn wait_foreground(job: &mut Job, job_control: bool) -> io::Result<()> {
+    if job_control {
+        give_terminal(job.pgid);
+    }
+
+    let result = wait_job(job);
+
+    if job_control {
+        take_terminal();
+    }
+
+    result
+}
+
+// Make cargo stop complaining about functions used for
+// tests
+#[allow(unused_imports, dead_code)]
+mod test {
+    use super::{Job, JobTable};
+
+    fn table() -> JobTable {
+        let mut table = JobTable::default();
+        table.add(Job::new(100, String::from("sleep 10"), vec![100]));
+        table.add(Job::new(200, String::from("vim notes.txt"), vec![200]));
+        table.add(Job::new(300, String::from("sleep 20 | cat"), vec![300, 301]));
+
+        table
+    }
+
+    #[test]
+    fn find_current() {
+        let table = table();
+
+        assert_eq!(table.find(None), Some(3));
+        assert_eq!(table.find(Some("%")), Some(3));
+        assert_eq!(table.find(Some("%%")), Some(3));
+        assert_eq!(table.find(Some("%+")), Some(3));
+        assert_eq!(table.find(Some("%-")), Some(2));
+    }
+
+    #[test]
+    fn find_number() {
+        let table = table();
+
+        assert_eq!(table.find(Some("%1")), Some(1));
+        assert_eq!(table.find(Some("2")), Some(2));
+        assert_eq!(table.find(Some("%4")), None);
+        assert_eq!(table.find(Some("%0")), None);
+    }
+
+    #[test]
+    fn find_name() {
+        let table = table();
+
+        assert_eq!(table.find(Some("%sleep")), Some(3));
+        assert_eq!(table.find(Some("%vim")), Some(2));
+        assert_eq!(table.find(Some("%?notes")), Some(2));
+        assert_eq!(table.find(Some("%?10")), Some(1));
+        assert_eq!(table.find(Some("%emacs")), None);
+    }
+
+    #[test]
+    fn find_after_remove() {
+        let mut table = table();
+        table.remove(3);
+
+        assert_eq!(table.find(Some("%+")), Some(2));
+        assert_eq!(table.find(Some("%-")), None);
+        assert_eq!(table.find(Some("%3")), None);
+    }
+}
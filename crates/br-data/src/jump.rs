@@ -0,0 +1,252 @@
+use std::{
+    fs::{self, OpenOptions},
+    io::{self, Write},
+    path::{Path, PathBuf},
+    time::{SystemTime, UNIX_EPOCH},
+};
+
+use crate::{get_config_dir, lock_file, write_atomic};
+
+// Directories beyond this are dropped, lowest score first
+const MAX_ENTRIES: usize = 500;
+// Visits are appended to the file, which is rewritten with one
+// line per directory once it grows past this
+const MAX_FILE_SIZE: u64 = 64 * 1024;
+
+const HOUR: u64 = 60 * 60;
+const DAY: u64 = 24 * HOUR;
+const WEEK: u64 = 7 * DAY;
+
+#[derive(Debug, PartialEq, Clone)]
+pub struct JumpEntry {
+    pub path:       String,
+    pub visits:     u64,
+    pub last_visit: u64,
+}
+
+impl JumpEntry {
+    // Visits weighted by how recently the directory was visited
+    pub fn score(&self, now: u64) -> f64 {
+        let age = now.saturating_sub(self.last_visit);
+        let weight = if age < HOUR {
+            4.0
+        } else if age < DAY {
+            2.0
+        } else if age < WEEK {
+            0.5
+        } else {
+            0.25
+        };
+
+        self.visits as f64 * weight
+    }
+}
+
+// Directories visited with 'cd' and friends, stored in the config
+// dir and used by 'z' to jump to frequent and recent directories
+#[derive(Debug, Default, Clone)]
+pub struct JumpDb {
+    pub entries: Vec<JumpEntry>,
+}
+
+impl JumpDb {
+    pub fn load() -> Self {
+        match db_path() {
+            Some(path) => Self::read(&path),
+            None => Self::default(),
+        }
+    }
+
+    // Lines for the same directory are merged as visits are
+    // appended by each session
+    fn read(path: &Path) -> Self {
+        let mut db = Self::default();
+        let contents = fs::read_to_string(path).unwrap_or_default();
+        for new_entry in contents.lines().filter_map(decode) {
+            match db.entries.iter_mut().find(|entry| entry.path == new_entry.path) {
+                Some(entry) => {
+                    entry.visits += new_entry.visits;
+                    entry.last_visit = entry.last_visit.max(new_entry.last_visit);
+                },
+                None => db.entries.push(new_entry),
+            }
+        }
+
+        db
+    }
+
+    // Replace the file with one line per directory, callers hold
+    // the lock
+    fn write(&mut self, path: &Path) -> io::Result<()> {
+        let now = now();
+        self.entries.sort_by(|a, b| b.score(now).total_cmp(&a.score(now)));
+        self.entries.truncate(MAX_ENTRIES);
+
+        let contents: String = self.entries.iter().map(encode).collect();
+        write_atomic(path, &contents)
+    }
+
+    // Existing directories matching every query term in order,
+    // with the last term in the final component, best first
+    pub fn matches(&self, query: &[String]) -> Vec<&JumpEntry> {
+        let query: Vec<String> = query.iter().map(|term| term.to_lowercase()).collect();
+
+        let mut matches: Vec<&JumpEntry> = self
+            .entries
+            .iter()
+            .filter(|entry| is_match(&entry.path, &query))
+            .filter(|entry| Path::new(&entry.path).is_dir())
+            .collect();
+
+        let now = now();
+        matches.sort_by(|a, b| b.score(now).total_cmp(&a.score(now)));
+
+        matches
+    }
+}
+
+// Record a visit by appending to the file, so sessions never
+// overwrite each other's visits
+pub fn record_visit(dir: &str) -> io::Result<()> {
+    // Directories with a newline can't be stored on one line
+    if dir.contains('\n') {
+        return Ok(());
+    }
+
+    let path = db_path().ok_or_else(no_config_dir)?;
+    let _lock = lock_file(&path)?;
+
+    let entry = JumpEntry {
+        path:       dir.to_string(),
+        visits:     1,
+        last_visit: now(),
+    };
+
+    let mut file = OpenOptions::new().create(true).append(true).open(&path)?;
+    file.write_all(encode(&entry).as_bytes())?;
+
+    if file.metadata()?.len() > MAX_FILE_SIZE {
+        JumpDb::read(&path).write(&path)?;
+    }
+
+    Ok(())
+}
+
+// Remove a directory so 'z' no longer jumps to it
+pub fn forget(dir: &str) -> io::Result<()> {
+    let path = db_path().ok_or_else(no_config_dir)?;
+    let _lock = lock_file(&path)?;
+
+    let mut db = JumpDb::read(&path);
+    db.entries.retain(|entry| entry.path != dir);
+    db.write(&path)
+}
+
+fn db_path() -> Option<PathBuf> {
+    get_config_dir().map(|dir| Path::new(&dir).join("jump_dirs"))
+}
+
+fn no_config_dir() -> io::Error {
+    io::Error::new(io::ErrorKind::NotFound, "No config directory")
+}
+
+fn is_match(path: &str, query: &[String]) -> bool {
+    let path = path.to_lowercase();
+
+    let mut rest = path.as_str();
+    for term in query {
+        match rest.find(term.as_str()) {
+            Some(idx) => rest = &rest[idx + term.len()..],
+            None => return false,
+        }
+    }
+
+    match (query.last(), Path::new(&path).file_name()) {
+        (Some(term), Some(name)) => name.to_string_lossy().contains(term.as_str()),
+        (Some(_), None) => false,
+        (None, _) => true,
+    }
+}
+
+fn encode(entry: &JumpEntry) -> String {
+    format!("{}\t{}\t{}\n", entry.visits, entry.last_visit, entry.path)
+}
+
+fn decode(line: &str) -> Option<JumpEntry> {
+    let mut fields = line.splitn(3, '\t');
+
+    Some(JumpEntry {
+        visits:     fields.next()?.parse().ok()?,
+        last_visit: fields.next()?.parse().ok()?,
+        path:       fields.next()?.to_string(),
+    })
+}
+
+pub fn now() -> u64 {
+    SystemTime::now().duration_since(UNIX_EPOCH).map(|time| time.as_secs()).unwrap_or_default()
+}
+
+// Make cargo stop complaining about functions used for
+// tests
+#[allow(unused_imports, dead_code)]
+mod test {
+    use super::{decode, encode, is_match, JumpEntry, DAY, HOUR, WEEK};
+
+    fn matches(path: &str, query: &[&str]) -> bool {
+        let query: Vec<String> = query.iter().map(|term| term.to_string()).collect();
+        is_match(path, &query)
+    }
+
+    fn entry(visits: u64, last_visit: u64) -> JumpEntry {
+        JumpEntry {
+            path: String::from("/tmp"),
+            visits,
+            last_visit,
+        }
+    }
+
+    #[test]
+    fn match_terms() {
+        assert!(matches("/home/user/projects/bransh", &["bransh"]));
+        assert!(matches("/home/user/projects/bransh", &["proj", "br"]));
+        assert!(matches("/home/user/Projects/Bransh", &["bransh"]));
+        assert!(matches("/home/user/projects/bransh", &[]));
+    }
+
+    #[test]
+    fn match_order() {
+        // Terms must appear in order
+        assert!(!matches("/home/user/projects/bransh", &["bransh", "proj"]));
+        // The last term must be in the final component
+        assert!(!matches("/home/user/projects/bransh", &["proj"]));
+        assert!(!matches("/", &["a"]));
+        // Terms can't overlap
+        assert!(!matches("/tmp/ab", &["ab", "b"]));
+    }
+
+    #[test]
+    fn score() {
+        let now = 10 * WEEK;
+
+        assert_eq!(entry(2, now).score(now), 8.0);
+        assert_eq!(entry(2, now - HOUR).score(now), 4.0);
+        assert_eq!(entry(2, now - DAY).score(now), 1.0);
+        assert_eq!(entry(2, now - WEEK).score(now), 0.5);
+        // Visits recorded in the future count as recent
+        assert_eq!(entry(1, now + DAY).score(now), 4.0);
+    }
+
+    #[test]
+    fn decode_lines() {
+        let entry = JumpEntry {
+            path:       String::from("/tmp/with\ttab"),
+            visits:     3,
+            last_visit: 1000,
+        };
+
+        assert_eq!(decode(encode(&entry).trim_end_matches('\n')), Some(entry));
+        assert_eq!(decode("3\t1000"), None);
+        assert_eq!(decode("x\t1000\t/tmp"), None);
+        assert_eq!(decode(""), None);
+    }
+}
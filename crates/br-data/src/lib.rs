@@ -1,8 +1,18 @@
+pub mod bookmark;
+pub mod cache;
 pub mod command;
 pub mod context;
+pub mod dirs;
+pub mod hook;
+pub mod job;
+pub mod jump;
 pub mod options;
+pub mod setting;
+pub mod signal;
+pub mod universal;
+pub mod variable;
 
-use std::env;
+use std::{env, fs, io, path::Path, process};
 
 pub fn get_config_dir() -> Option<String> {
     if cfg!(unix) {
@@ -23,3 +33,48 @@ pub fn get_config_dir() -> Option<String> {
         None
     }
 }
+
+// Write to a temporary file and rename it over path, so other
+// sessions never read a partially written file
+pub fn write_atomic(path: &Path, contents: &str) -> io::Result<()> {
+    if let Some(dir) = path.parent() {
+        fs::create_dir_all(dir)?;
+    }
+
+    let tmp_path = path.with_extension(format!("tmp{}", process::id()));
+    fs::write(&tmp_path, contents)?;
+    fs::rename(&tmp_path, path)
+}
+
+// Held while a shared file is changed so other sessions wait for
+// it, released when dropped
+#[cfg(unix)]
+pub type FileLock = fs::File;
+#[cfg(windows)]
+pub type FileLock = ();
+
+// Exclusive lock on a '.lock' file next to path, only writers take
+// it so it also creates the config dir
+#[cfg(unix)]
+pub fn lock_file(path: &Path) -> io::Result<FileLock> {
+    use std::{fs::OpenOptions, os::unix::io::AsRawFd};
+
+    if let Some(dir) = path.parent() {
+        fs::create_dir_all(dir)?;
+    }
+
+    let file = OpenOptions::new().create(true).write(true).truncate(false).open(path.with_extension("lock"))?;
+    if unsafe { libc::flock(file.as_raw_fd(), libc::LOCK_EX) } == -1 {
+        return Err(io::Error::last_os_error());
+    }
+
+    Ok(file)
+}
+
+#[cfg(windows)]
+pub fn lock_file(path: &Path) -> io::Result<FileLock> {
+    match path.parent() {
+        Some(dir) => fs::create_dir_all(dir),
+        None => Ok(()),
+    }
+}
@@ -1,4 +1,4 @@
-use gumdrop::Options as Opts;
+use gumdrop::{Options as Opts, ParsingStyle};
 
 #[derive(Debug, Opts)]
 pub struct Options {
@@ -13,10 +13,21 @@ pub struct Options {
 
     #[options(no_long, help = "execute command and exit")]
     pub command: Option<String>,
+
+    #[options(short = "s", no_long, help = "read commands from stdin")]
+    pub stdin: bool,
+
+    #[options(short = "i", no_long, help = "force interactive mode")]
+    pub interactive: bool,
+
+    #[options(free, help = "script file to run, followed by its arguments")]
+    pub args: Vec<String>,
 }
 
 impl Options {
     pub fn parse() -> Self {
-        Self::parse_args_default_or_exit()
+        // Stop at the script path so that any flags after it
+        // are passed to the script instead of Bransh
+        Self::parse_args_or_exit(ParsingStyle::StopAtFirstFree)
     }
 }
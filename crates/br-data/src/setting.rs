@@ -0,0 +1,199 @@
+#[derive(Debug, PartialEq, Clone, Copy, Eq, Hash)]
+pub enum SettingType {
+    Bool,
+    Number,
+    Text,
+}
+
+impl SettingType {
+    pub fn name(&self) -> &'static str {
+        match self {
+            Self::Bool => "bool",
+            Self::Number => "number",
+            Self::Text => "text",
+        }
+    }
+}
+
+// Shell setting stored as a variable, the value is checked when
+// it's set
+#[derive(Debug, Clone, Copy)]
+pub struct Setting {
+    pub name:         &'static str,
+    pub setting_type: SettingType,
+    pub default:      &'static str,
+    pub description:  &'static str,
+}
+
+pub const SETTINGS: &[Setting] = &[
+    Setting {
+        name:         "PROMPT",
+        setting_type: SettingType::Text,
+        default:      "{WD} | ",
+        description:  "Prompt format, supports {WD}, {HOST}, {OS} and {USER}",
+    },
+    Setting {
+        name:         "P_HOME_TRUNC",
+        setting_type: SettingType::Bool,
+        default:      "true",
+        description:  "Replace the home directory in {WD} with P_HOME_CHAR",
+    },
+    Setting {
+        name:         "P_HOME_CHAR",
+        setting_type: SettingType::Text,
+        default:      "~",
+        description:  "Text shown in place of the home directory",
+    },
+    Setting {
+        name:         "P_DIR_TRUNC",
+        setting_type: SettingType::Number,
+        default:      "2",
+        description:  "Number of directories shown in {WD}, 0 shows all",
+    },
+    Setting {
+        name:         "P_DIR_CHAR",
+        setting_type: SettingType::Text,
+        default:      "…",
+        description:  "Text shown in place of truncated directories",
+    },
+    Setting {
+        name:         "P_BOOKMARKS",
+        setting_type: SettingType::Bool,
+        default:      "true",
+        description:  "Show bookmarked directories in {WD} as @name",
+    },
+    Setting {
+        name:         "SYN_HIGHLIGHTING",
+        setting_type: SettingType::Bool,
+        default:      "true",
+        description:  "Highlight commands while typing",
+    },
+    Setting {
+        name:         "BG_OUTPUT",
+        setting_type: SettingType::Bool,
+        default:      "true",
+        description:  "Show the output of background jobs",
+    },
+    Setting {
+        name:         "AUTO_CD",
+        setting_type: SettingType::Bool,
+        default:      "false",
+        description:  "Change to directories typed as commands",
+    },
+    Setting {
+        name:         "OPT_PIPEFAIL",
+        setting_type: SettingType::Bool,
+        default:      "false",
+        description:  "Pipelines fail if any command in them fails, set with 'set -o pipefail'",
+    },
+    Setting {
+        name:         "OPT_ERREXIT",
+        setting_type: SettingType::Bool,
+        default:      "false",
+        description:  "Exit on the first failing command, set with 'set -e'",
+    },
+    Setting {
+        name:         "OPT_NOUNSET",
+        setting_type: SettingType::Bool,
+        default:      "false",
+        description:  "Treat unset variables as an error, set with 'set -u'",
+    },
+    Setting {
+        name:         "OPT_XTRACE",
+        setting_type: SettingType::Bool,
+        default:      "false",
+        description:  "Print commands before running them, set with 'set -x'",
+    },
+    Setting {
+        name:         "PS4",
+        setting_type: SettingType::Text,
+        default:      "+ ",
+        description:  "Prefix for commands printed by OPT_XTRACE",
+    },
+];
+
+pub fn find_setting(name: &str) -> Option<&'static Setting> {
+    SETTINGS.iter().find(|setting| setting.name == name)
+}
+
+impl Setting {
+    pub fn validate(&self, value: &str) -> Result<(), String> {
+        let valid = match self.setting_type {
+            SettingType::Bool => value.parse::<bool>().is_ok(),
+            SettingType::Number => value.parse::<usize>().is_ok(),
+            SettingType::Text => true,
+        };
+
+        if valid {
+            Ok(())
+        } else {
+            Err(format!(
+                "Invalid value '{}' for {}! Expected a {}",
+                value,
+                self.name,
+                self.setting_type.name()
+            ))
+        }
+    }
+
+    // Values offered by tab completion
+    pub fn values(&self) -> Vec<String> {
+        match self.setting_type {
+            SettingType::Bool => vec![String::from("true"), String::from("false")],
+            _ => vec![self.default.to_string()],
+        }
+    }
+}
+
+// Make cargo stop complaining about functions used for
+// tests
+#[allow(unused_imports, dead_code)]
+mod test {
+    use super::find_setting;
+    use crate::{context::Context, variable::VarValue};
+
+    fn validate(name: &str, value: &str) -> bool {
+        find_setting(name).map(|setting| setting.validate(value).is_ok()).unwrap_or_default()
+    }
+
+    #[test]
+    fn validate_bool() {
+        assert!(validate("AUTO_CD", "true"));
+        assert!(validate("AUTO_CD", "false"));
+        assert!(!validate("AUTO_CD", "yes"));
+        assert!(!validate("AUTO_CD", ""));
+    }
+
+    #[test]
+    fn validate_number() {
+        assert!(validate("P_DIR_TRUNC", "0"));
+        assert!(validate("P_DIR_TRUNC", "12"));
+        assert!(!validate("P_DIR_TRUNC", "abc"));
+        assert!(!validate("P_DIR_TRUNC", "-1"));
+        assert!(!validate("P_DIR_TRUNC", "1.5"));
+    }
+
+    #[test]
+    fn validate_text() {
+        assert!(validate("PROMPT", ""));
+        assert!(validate("PROMPT", "{USER}@{HOST} > "));
+    }
+
+    #[test]
+    fn defaults_are_valid() {
+        for setting in super::SETTINGS {
+            assert_eq!(setting.validate(setting.default), Ok(()), "{}", setting.name);
+        }
+    }
+
+    #[test]
+    fn validate_variable() {
+        let ctx = Context::default();
+        let list = VarValue::List(vec![String::from("1"), String::from("2")]);
+
+        assert!(ctx.validate_variable("P_DIR_TRUNC", &VarValue::Str(String::from("3"))).is_ok());
+        assert!(ctx.validate_variable("P_DIR_TRUNC", &VarValue::Str(String::from("abc"))).is_err());
+        assert!(ctx.validate_variable("P_DIR_TRUNC", &list).is_err());
+        assert!(ctx.validate_variable("NOT_A_SETTING", &list).is_ok());
+    }
+}
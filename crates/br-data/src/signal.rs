@@ -0,0 +1,148 @@
+#[cfg(unix)]
+use std::sync::atomic::{AtomicBool, Ordering};
+
+// Pseudo signal used by 'trap' for handlers run when the shell
+// exits
+pub const EXIT: i32 = 0;
+
+#[cfg(unix)]
+pub const SIGNALS: &[(&str, i32)] = &[
+    ("EXIT", EXIT),
+    ("HUP", libc::SIGHUP),
+    ("INT", libc::SIGINT),
+    ("QUIT", libc::SIGQUIT),
+    ("ILL", libc::SIGILL),
+    ("TRAP", libc::SIGTRAP),
+    ("ABRT", libc::SIGABRT),
+    ("BUS", libc::SIGBUS),
+    ("FPE", libc::SIGFPE),
+    ("KILL", libc::SIGKILL),
+    ("USR1", libc::SIGUSR1),
+    ("SEGV", libc::SIGSEGV),
+    ("USR2", libc::SIGUSR2),
+    ("PIPE", libc::SIGPIPE),
+    ("ALRM", libc::SIGALRM),
+    ("TERM", libc::SIGTERM),
+    ("CHLD", libc::SIGCHLD),
+    ("CONT", libc::SIGCONT),
+    ("STOP", libc::SIGSTOP),
+    ("TSTP", libc::SIGTSTP),
+    ("TTIN", libc::SIGTTIN),
+    ("TTOU", libc::SIGTTOU),
+    ("URG", libc::SIGURG),
+    ("XCPU", libc::SIGXCPU),
+    ("XFSZ", libc::SIGXFSZ),
+    ("VTALRM", libc::SIGVTALRM),
+    ("PROF", libc::SIGPROF),
+    ("WINCH", libc::SIGWINCH),
+    ("IO", libc::SIGIO),
+    ("SYS", libc::SIGSYS),
+];
+
+#[cfg(windows)]
+pub const SIGNALS: &[(&str, i32)] = &[("EXIT", EXIT), ("INT", 2), ("TERM", 15)];
+
+// Get a signal from a name such as 'INT', 'SIGINT' or '2'
+pub fn from_name(name: &str) -> Option<i32> {
+    if let Ok(number) = name.parse::<i32>() {
+        return SIGNALS.iter().find(|(_, signal)| *signal == number).map(|(_, signal)| *signal);
+    }
+
+    let name = name.to_uppercase();
+    let name = name.strip_prefix("SIG").unwrap_or(&name);
+    SIGNALS.iter().find(|(sig_name, _)| *sig_name == name).map(|(_, signal)| *signal)
+}
+
+pub fn name(signal: i32) -> String {
+    match SIGNALS.iter().find(|(_, sig)| *sig == signal) {
+        Some((name, _)) => name.to_string(),
+        None => signal.to_string(),
+    }
+}
+
+#[cfg(unix)]
+const MAX_SIGNAL: usize = 65;
+
+#[cfg(unix)]
+#[allow(clippy::declare_interior_mutable_const)]
+const NOT_PENDING: AtomicBool = AtomicBool::new(false);
+
+#[cfg(unix)]
+static PENDING: [AtomicBool; MAX_SIGNAL] = [NOT_PENDING; MAX_SIGNAL];
+
+#[cfg(unix)]
+static INTERACTIVE: AtomicBool = AtomicBool::new(false);
+
+#[cfg(unix)]
+extern "C" fn handle_signal(signal: libc::c_int) {
+    if let Some(pending) = PENDING.get(signal as usize) {
+        pending.store(true, Ordering::SeqCst);
+    }
+}
+
+// Setup the signals used by the shell itself, interactive shells
+// catch SIGINT and SIGHUP and ignore SIGQUIT and SIGTERM
+#[cfg(unix)]
+pub fn init_signals(interactive: bool) {
+    INTERACTIVE.store(interactive, Ordering::SeqCst);
+
+    for signal in &[libc::SIGINT, libc::SIGHUP, libc::SIGQUIT, libc::SIGTERM] {
+        reset(*signal);
+    }
+}
+
+// Record the signal so it can be handled by the shell, syscalls
+// are interrupted rather than restarted so waits return early
+#[cfg(unix)]
+pub fn catch(signal: i32) {
+    unsafe {
+        let mut action: libc::sigaction = std::mem::zeroed();
+        action.sa_sigaction = handle_signal as *const () as libc::sighandler_t;
+        libc::sigemptyset(&mut action.sa_mask);
+        libc::sigaction(signal, &action, std::ptr::null_mut());
+    }
+}
+
+#[cfg(unix)]
+pub fn ignore(signal: i32) {
+    unsafe {
+        libc::signal(signal, libc::SIG_IGN);
+    }
+}
+
+// Restore the disposition the shell uses when no trap is set
+#[cfg(unix)]
+pub fn reset(signal: i32) {
+    if !INTERACTIVE.load(Ordering::SeqCst) {
+        unsafe {
+            libc::signal(signal, libc::SIG_DFL);
+        }
+
+        return;
+    }
+
+    match signal {
+        libc::SIGINT | libc::SIGHUP => catch(signal),
+        libc::SIGQUIT | libc::SIGTERM | libc::SIGTSTP | libc::SIGTTIN | libc::SIGTTOU => ignore(signal),
+        _ => unsafe {
+            libc::signal(signal, libc::SIG_DFL);
+        },
+    }
+}
+
+// Signals received but not yet handled
+#[cfg(unix)]
+pub fn pending() -> Vec<i32> {
+    (1..MAX_SIGNAL)
+        .filter(|signal| PENDING[*signal].load(Ordering::SeqCst))
+        .map(|signal| signal as i32)
+        .collect()
+}
+
+#[cfg(unix)]
+pub fn take_pending() -> Vec<i32> {
+    (1..MAX_SIGNAL)
+        .filter(|signal| PENDING[*signal].swap(false, Ordering::SeqCst))
+        .map(|signal| signal as i32)
+        .collect()
+}
@@ -0,0 +1,218 @@
+use std::{
+    collections::BTreeMap,
+    fs,
+    io,
+    path::{Path, PathBuf},
+    time::SystemTime,
+};
+
+use crate::{
+    get_config_dir,
+    lock_file,
+    variable::{VarValue, Variable},
+    write_atomic,
+    FileLock,
+};
+
+// Universal variables are stored in a file in the config dir,
+// shared by every running session. The file is reloaded when
+// it's modified by another session
+#[derive(Debug, Default, Clone)]
+pub struct UniversalStore {
+    path:     Option<PathBuf>,
+    modified: Option<SystemTime>,
+    loaded:   bool,
+}
+
+impl UniversalStore {
+    pub fn new() -> Self {
+        Self {
+            path: get_config_dir().map(|dir| Path::new(&dir).join("universal_vars")),
+            ..Self::default()
+        }
+    }
+
+    pub fn is_stale(&self) -> bool {
+        match &self.path {
+            Some(path) => !self.loaded || modified(path) != self.modified,
+            None => false,
+        }
+    }
+
+    // Taken before loading the file to change it, so sessions
+    // setting variables at the same time don't lose updates
+    pub fn lock(&self) -> io::Result<Option<FileLock>> {
+        match &self.path {
+            Some(path) => lock_file(path).map(Some),
+            None => Ok(None),
+        }
+    }
+
+    pub fn load(&mut self) -> BTreeMap<String, Variable> {
+        let path = match &self.path {
+            Some(path) => path,
+            None => return BTreeMap::new(),
+        };
+
+        self.loaded = true;
+        self.modified = modified(path);
+
+        match fs::read_to_string(path) {
+            Ok(contents) => contents.lines().filter_map(decode).collect(),
+            Err(_) => BTreeMap::new(),
+        }
+    }
+
+    pub fn save(&mut self, vars: &BTreeMap<String, Variable>) -> io::Result<()> {
+        let path = match &self.path {
+            Some(path) => path,
+            None => return Err(io::Error::new(io::ErrorKind::NotFound, "No config directory")),
+        };
+
+        let contents: String = vars.iter().map(|(name, var)| encode(name, var) + "\n").collect();
+        write_atomic(path, &contents)?;
+
+        self.modified = modified(path);
+
+        Ok(())
+    }
+}
+
+fn modified(path: &Path) -> Option<SystemTime> {
+    path.metadata().and_then(|metadata| metadata.modified()).ok()
+}
+
+// Each variable is stored on one line as its type, name and
+// items separated by tabs, read only variables start with an
+// extra 'readonly' field
+fn encode(name: &str, var: &Variable) -> String {
+    let (kind, items) = match &var.value {
+        VarValue::Str(value) => ("str", vec![value.clone()]),
+        VarValue::List(items) => ("list", items.clone()),
+        VarValue::Map(map) => (
+            "map",
+            map.iter().flat_map(|(key, value)| vec![key.clone(), value.clone()]).collect(),
+        ),
+    };
+
+    let mut fields = Vec::new();
+    if var.readonly {
+        fields.push(String::from("readonly"));
+    }
+
+    fields.push(kind.to_string());
+    fields.push(name.to_string());
+    fields.extend(items.iter().map(|item| escape(item)));
+
+    fields.join("\t")
+}
+
+fn decode(line: &str) -> Option<(String, Variable)> {
+    let mut fields = line.split('\t').peekable();
+    let readonly = fields.next_if_eq(&"readonly").is_some();
+    let kind = fields.next()?;
+    let name = fields.next()?.to_string();
+    let items: Vec<String> = fields.map(unescape).collect();
+
+    let value = match kind {
+        "str" => VarValue::Str(items.into_iter().next().unwrap_or_default()),
+        "list" => VarValue::List(items),
+        "map" => VarValue::Map(
+            items
+                .chunks(2)
+                .filter(|pair| pair.len() == 2)
+                .map(|pair| (pair[0].clone(), pair[1].clone()))
+                .collect(),
+        ),
+        _ => return None,
+    };
+
+    let mut var = Variable::new(value);
+    var.readonly = readonly;
+    var.universal = true;
+
+    Some((name, var))
+}
+
+fn escape(item: &str) -> String {
+    item.replace('\\', "\\\\").replace('\t', "\\t").replace('\n', "\\n")
+}
+
+fn unescape(item: &str) -> String {
+    let mut unescaped = String::new();
+    let mut chars = item.chars();
+    while let Some(ch) = chars.next() {
+        if ch != '\\' {
+            unescaped.push(ch);
+            continue;
+        }
+
+        match chars.next() {
+            Some('t') => unescaped.push('\t'),
+            Some('n') => unescaped.push('\n'),
+            Some(ch) => unescaped.push(ch),
+            None => unescaped.push('\\'),
+        }
+    }
+
+    unescaped
+}
+
+// Make cargo stop complaining about functions used for
+// tests
+#[allow(unused_imports, dead_code)]
+mod test {
+    use std::collections::BTreeMap;
+
+    use super::{decode, encode, escape, unescape};
+    use crate::variable::{VarValue, Variable};
+
+    fn round_trip(value: VarValue, readonly: bool) {
+        let mut var = Variable::new(value);
+        var.readonly = readonly;
+        var.universal = true;
+
+        let line = encode("NAME", &var);
+        assert!(!line.contains('\n'));
+        assert_eq!(decode(&line), Some((String::from("NAME"), var)));
+    }
+
+    #[test]
+    fn escapes() {
+        for item in ["plain", "tab\there", "two\nlines", "back\\slash\\t", "\\", "trailing\\", ""] {
+            let escaped = escape(item);
+            assert!(!escaped.contains('\t') && !escaped.contains('\n'));
+            assert_eq!(unescape(&escaped), item);
+        }
+    }
+
+    #[test]
+    fn values() {
+        round_trip(VarValue::Str(String::from("a\tb\nc\\d")), false);
+        round_trip(VarValue::Str(String::new()), false);
+        round_trip(VarValue::List(vec![String::from("1"), String::from("two\tthree")]), false);
+
+        let mut map = BTreeMap::new();
+        map.insert(String::from("key\n"), String::from("value\\"));
+        map.insert(String::from("other"), String::new());
+        round_trip(VarValue::Map(map), false);
+    }
+
+    #[test]
+    fn readonly() {
+        round_trip(VarValue::Str(String::from("fixed")), true);
+        round_trip(VarValue::Str(String::from("readonly")), true);
+
+        let (_, var) = decode("str\tNAME\tvalue").unwrap_or_default();
+        assert!(!var.readonly);
+        assert!(var.universal);
+    }
+
+    #[test]
+    fn invalid() {
+        assert_eq!(decode(""), None);
+        assert_eq!(decode("str"), None);
+        assert_eq!(decode("number\tNAME\t1"), None);
+        assert_eq!(decode("readonly\tNAME\t1"), None);
+    }
+}
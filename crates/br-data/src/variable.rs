@@ -0,0 +1,120 @@
+use std::{collections::BTreeMap, fmt};
+
+#[derive(Debug, PartialEq, Clone, Eq, Hash)]
+pub enum VarValue {
+    Str(String),
+    List(Vec<String>),
+    Map(BTreeMap<String, String>),
+}
+
+impl Default for VarValue {
+    fn default() -> Self {
+        Self::Str(String::new())
+    }
+}
+
+impl fmt::Display for VarValue {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.items().join(" "))
+    }
+}
+
+impl VarValue {
+    // Values the variable expands to, lists and maps expand to
+    // one argument per item
+    pub fn items(&self) -> Vec<String> {
+        match self {
+            Self::Str(value) => vec![value.clone()],
+            Self::List(items) => items.clone(),
+            Self::Map(map) => map.values().cloned().collect(),
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        match self {
+            Self::Str(value) => value.chars().count(),
+            Self::List(items) => items.len(),
+            Self::Map(map) => map.len(),
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    // Get items using an index such as '1', '-1', '2..', '..3' or
+    // '2..3', indexes start at 1 and ranges are inclusive. Maps
+    // are indexed by key and strings are treated as a one item
+    // list
+    pub fn index(&self, index: &str) -> Vec<String> {
+        let items = match self {
+            Self::Map(map) => return map.get(index).cloned().into_iter().collect(),
+            Self::Str(value) => vec![value.clone()],
+            Self::List(items) => items.clone(),
+        };
+
+        let (start, end) = match index.split_once("..") {
+            Some((start, end)) => (
+                if start.is_empty() { Some(1) } else { start.parse::<isize>().ok() },
+                if end.is_empty() { Some(-1) } else { end.parse::<isize>().ok() },
+            ),
+            None => {
+                let idx = index.parse::<isize>().ok();
+                (idx, idx)
+            },
+        };
+
+        // Ranges past the end stop at the last item
+        let end = end.map(|idx| idx.min(items.len() as isize));
+
+        let start = start.and_then(|idx| resolve(idx, items.len()));
+        let end = end.and_then(|idx| resolve(idx, items.len()));
+        let (start, end) = match (start, end) {
+            (Some(start), Some(end)) if start <= end => (start, end),
+            _ => return Vec::new(),
+        };
+
+        items[start..=end].to_vec()
+    }
+}
+
+// Convert a 1 based index, or negative from the end, into a 0
+// based one
+fn resolve(idx: isize, len: usize) -> Option<usize> {
+    let resolved = if idx < 0 { len as isize + idx } else { idx - 1 };
+    if resolved < 0 || resolved as usize >= len {
+        return None;
+    }
+
+    Some(resolved as usize)
+}
+
+#[derive(Debug, Default, PartialEq, Clone, Eq, Hash)]
+pub struct Variable {
+    pub value:     VarValue,
+    // Exported variables are passed to child processes
+    pub exported:  bool,
+    pub readonly:  bool,
+    // Universal variables are saved and shared between sessions
+    pub universal: bool,
+}
+
+impl Variable {
+    pub const fn new(value: VarValue) -> Self {
+        Self {
+            value,
+            exported: false,
+            readonly: false,
+            universal: false,
+        }
+    }
+
+    // Value passed to child processes, lists are joined with ':'
+    // the same as PATH
+    pub fn export_value(&self) -> String {
+        match &self.value {
+            VarValue::List(items) => items.join(":"),
+            value => value.to_string(),
+        }
+    }
+}
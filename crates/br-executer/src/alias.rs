@@ -0,0 +1,78 @@
+use br_data::context::Context;
+use br_parser::{parse_command, parser::CommandList, parser::ParseError, Command};
+
+pub fn expand_aliases(commands: CommandList, ctx: &Context) -> Result<CommandList, ParseError> {
+    let mut expanded = CommandList::new();
+    for cmd in commands {
+        expanded.append(&mut expand_command(cmd, ctx, &[])?);
+    }
+
+    Ok(expanded)
+}
+
+fn expand_command(mut cmd: Command, ctx: &Context, seen: &[String]) -> Result<CommandList, ParseError> {
+    if cmd.escaped {
+        return Ok(vec![cmd]);
+    }
+
+    // 'command name' runs name without alias expansion
+    if cmd.command == "command" && !cmd.args.is_empty() {
+        cmd.command = cmd.args.remove(0);
+        return Ok(vec![cmd]);
+    }
+
+    // Aliases aren't expanded within themselves to prevent
+    // infinite loops
+    let value = match ctx.aliases.get(&cmd.command) {
+        Some(value) if !seen.contains(&cmd.command) => value.clone(),
+        _ => return Ok(vec![cmd]),
+    };
+
+    let mut seen = seen.to_vec();
+    seen.push(cmd.command.clone());
+
+    let mut alias_cmds = CommandList::new();
+    for alias_cmd in parse_command(value.clone(), ctx)? {
+        alias_cmds.append(&mut expand_command(alias_cmd, ctx, &seen)?);
+    }
+
+    let mut args = cmd.args;
+    if alias_cmds.is_empty() {
+        if args.is_empty() {
+            return Ok(alias_cmds);
+        }
+
+        cmd.command = args.remove(0);
+        cmd.args = args;
+        return Ok(vec![cmd]);
+    }
+
+    // Trailing whitespace means the next word should also be
+    // checked for aliases
+    if value.ends_with(' ') && !args.is_empty() {
+        let next_cmd = Command {
+            command: args.remove(0),
+            args: std::mem::take(&mut args),
+            ..Command::default()
+        };
+
+        let mut next_cmds = expand_command(next_cmd, ctx, &[])?.into_iter();
+        if let Some(first) = next_cmds.next() {
+            let last = alias_cmds.last_mut().unwrap();
+            last.args.push(first.command);
+            last.args.extend(first.args);
+            last.redirects.extend(first.redirects);
+            last.output_type = first.output_type;
+        }
+
+        alias_cmds.extend(next_cmds);
+    }
+
+    let last = alias_cmds.last_mut().unwrap();
+    last.args.extend(args);
+    last.redirects.extend(cmd.redirects);
+    last.background |= cmd.background;
+    last.output_type = cmd.output_type;
+
+    Ok(alias_cmds)
+}
@@ -1,158 +1,780 @@
-#[cfg(unix)]
-use std::os::unix::process::ExitStatusExt;
 use std::{
-    path::PathBuf,
-    process::{Child, Command, ExitStatus, Stdio},
+    env,
+    fs::{File, OpenOptions},
+    io::{self, pipe, PipeReader, PipeWriter, Read, Write},
+    path::{Path, PathBuf},
+    process::{Child, Command, Stdio},
     str::FromStr,
 };
 
-use br_command::load_builtins;
-use br_data::context::Context;
-use br_parser::{parse_command, OutputType};
+#[cfg(unix)]
+use std::os::unix::io::{AsRawFd, RawFd};
+
+#[cfg(unix)]
+use br_data::job::{self, Job, JobState};
+use br_data::{
+    command::{BrBuiltin, CommandIo, ExecOutcome},
+    context::Context,
+    dirs,
+    hook::HookEvent,
+    jump,
+    signal,
+    variable::VarValue,
+};
+use br_parser::{parse_command, Command as BrCommand, OutputType, RedirectType};
+
+use crate::{alias::expand_aliases, not_found::command_not_found};
+
+mod alias;
+mod not_found;
+
+// Output is only redirected through file descriptors on unix
+#[cfg(windows)]
+type RawFd = i32;
+
+// Run the handlers for signals received since the last call,
+// interactive shells hang up their jobs on SIGHUP
+#[cfg(unix)]
+pub fn run_traps(ctx: &mut Context) -> ExecOutcome {
+    for sig in signal::take_pending() {
+        match ctx.traps.get(&sig).cloned() {
+            Some(handler) => {
+                let output = run_handler(ctx, handler);
+                if let ExecOutcome::Exit(_) = output {
+                    return output;
+                }
+            },
+            None if sig == libc::SIGHUP => {
+                for job in &ctx.jobs.jobs {
+                    job::signal_job(job, libc::SIGHUP);
+                    job::signal_job(job, libc::SIGCONT);
+                }
+
+                return ExecOutcome::Exit(128 + sig);
+            },
+            None => {},
+        }
+    }
+
+    ExecOutcome::Status(0)
+}
+
+// Run the on_exit hooks followed by the EXIT trap
+pub fn run_exit_handlers(ctx: &mut Context) {
+    run_hooks(ctx, HookEvent::OnExit, &[]);
 
-#[allow(clippy::field_reassign_with_default)]
-pub fn execute_once(command: String) {
-    let mut ctx = Context::default();
-    load_builtins(&mut ctx);
-    ctx.cli.command_buffer = command;
+    if let Some(handler) = ctx.traps.remove(&signal::EXIT) {
+        run_handler(ctx, handler);
+    }
+}
+
+// Run each command registered for the event with the arguments
+// appended
+pub fn run_hooks(ctx: &mut Context, event: HookEvent, args: &[String]) {
+    let commands = match ctx.hooks.get(&event) {
+        Some(commands) => commands.clone(),
+        None => return,
+    };
 
-    execute(&mut ctx);
+    for command in commands {
+        let mut words = vec![command];
+        words.extend(args.iter().map(|arg| quote_word(arg)));
+
+        run_handler(ctx, words.join(" "));
+    }
 }
 
-pub fn execute(ctx: &mut Context) -> Option<i32> {
-    let commands_wrapped = parse_command(ctx.cli.command_buffer.clone(), ctx);
+fn is_command(name: &str, ctx: &Context) -> bool {
+    ctx.builtins.iter().any(|builtin| builtin.name == name) || ctx.commands.borrow_mut().lookup(name).is_some()
+}
+
+fn run_handler(ctx: &mut Context, handler: String) -> ExecOutcome {
+    let buffer = std::mem::replace(&mut ctx.cli.command_buffer, handler);
+    let last_status = ctx.last_status;
+
+    let output = execute(ctx);
+
+    // Handlers don't change the exit code seen by the
+    // interrupted command
+    ctx.cli.command_buffer = buffer;
+    ctx.last_status = last_status;
+
+    output
+}
+
+pub fn execute(ctx: &mut Context) -> ExecOutcome {
+    // Variables are expanded while parsing so pick up universal
+    // variables set by other sessions first
+    ctx.sync_universal();
+
+    let commands_wrapped =
+        parse_command(ctx.cli.command_buffer.clone(), ctx).and_then(|cmds| expand_aliases(cmds, ctx));
     let commands = match commands_wrapped {
         Ok(cmds) => cmds,
         Err(why) => {
             eprintln!("{}", why);
 
-            return Some(-1);
+            ctx.last_status = ExecOutcome::ParseError.code();
+            if ctx.get_variable("OPT_ERREXIT", false, false) {
+                return ExecOutcome::Exit(ctx.last_status);
+            }
+
+            return ExecOutcome::ParseError;
         },
     };
 
-    if commands.is_empty() {
-        return Some(0);
+    let mut last_output = ExecOutcome::Status(0);
+    let mut joiner = OutputType::Ignore;
+
+    let mut commands = commands.into_iter();
+    while let Some(cmd) = commands.next() {
+        // Group piped commands so they can be run together
+        let mut pipeline = vec![cmd];
+        while pipeline[pipeline.len() - 1].output_type == OutputType::Pipe {
+            match commands.next() {
+                Some(cmd) => pipeline.push(cmd),
+                None => break,
+            }
+        }
+
+        let skip = match joiner {
+            OutputType::Depend => !last_output.is_success(),
+            OutputType::DependNot => last_output.is_success(),
+            _ => false,
+        };
+
+        joiner = pipeline[pipeline.len() - 1].output_type.clone();
+        if skip {
+            continue;
+        }
+
+        if ctx.get_variable("OPT_XTRACE", false, false) {
+            let prefix = ctx.get_variable("PS4", String::from("+ "), false);
+            eprintln!("{}{}", prefix, describe_pipeline(&pipeline));
+        }
+
+        let old_dir = env::current_dir().ok();
+
+        last_output = run_pipeline(pipeline, ctx);
+        ctx.last_status = last_output.code();
+
+        if env::current_dir().ok() != old_dir {
+            // Remember the new directory so 'z' can jump to it
+            if let Err(why) = jump::record_visit(&dirs::current_dir().to_string_lossy()) {
+                eprintln!("Unable to save directory history! {}", why);
+            }
+
+            run_hooks(ctx, HookEvent::Chpwd, &[]);
+        }
+
+        if last_output.is_control_flow() {
+            return last_output;
+        }
+
+        // Failures that are checked by '&&' or '||' don't count
+        let checked = matches!(joiner, OutputType::Depend | OutputType::DependNot);
+        if !last_output.is_success() && !checked && ctx.get_variable("OPT_ERREXIT", false, false) {
+            return ExecOutcome::Exit(last_output.code());
+        }
     }
 
-    let mut last_command = None;
-    let mut last_output = 0;
-    let mut joiner = OutputType::Ignore;
+    last_output
+}
 
-    let mut commands = commands.into_iter().peekable();
-    'cmdloop: while let Some(mut cmd) = commands.next() {
-        match joiner {
-            OutputType::Ignore | OutputType::Pipe => {},
-            OutputType::Depend => {
-                if last_output != 0 {
-                    continue;
-                }
+enum Input {
+    Inherit,
+    Pipe(PipeReader),
+}
+
+impl Input {
+    fn into_stdio(self) -> Stdio {
+        match self {
+            Self::Inherit => Stdio::inherit(),
+            Self::Pipe(reader) => Stdio::from(reader),
+        }
+    }
+
+    fn into_reader(self) -> Box<dyn Read> {
+        match self {
+            Self::Inherit => Box::new(io::stdin()),
+            Self::Pipe(reader) => Box::new(reader),
+        }
+    }
+}
+
+enum Output {
+    Inherit,
+    Null,
+    Pipe(PipeWriter),
+    File(File),
+}
+
+impl Output {
+    fn into_stdio(self) -> Stdio {
+        match self {
+            Self::Inherit => Stdio::inherit(),
+            Self::Null => Stdio::null(),
+            Self::Pipe(writer) => Stdio::from(writer),
+            Self::File(file) => Stdio::from(file),
+        }
+    }
+
+    fn into_writer(self) -> Box<dyn Write> {
+        match self {
+            Self::Inherit => Box::new(io::stdout()),
+            Self::Null => Box::new(io::sink()),
+            Self::Pipe(writer) => Box::new(writer),
+            Self::File(file) => Box::new(file),
+        }
+    }
+
+    // File descriptor to point the shell's stdout at when
+    // commands are run from within the shell
+    #[cfg(unix)]
+    fn raw_fd(&self) -> Option<RawFd> {
+        match self {
+            Self::Inherit | Self::Null => None,
+            Self::Pipe(writer) => Some(writer.as_raw_fd()),
+            Self::File(file) => Some(file.as_raw_fd()),
+        }
+    }
+
+    #[cfg(windows)]
+    fn raw_fd(&self) -> Option<RawFd> {
+        None
+    }
+}
+
+enum PipelineChild {
+    Process(Child),
+    #[cfg(unix)]
+    Forked(libc::pid_t),
+    Finished(ExecOutcome),
+}
+
+impl PipelineChild {
+    #[cfg(unix)]
+    fn pid(&self) -> Option<i32> {
+        match self {
+            Self::Process(child) => Some(child.id() as i32),
+            Self::Forked(pid) => Some(*pid),
+            Self::Finished(_) => None,
+        }
+    }
+
+    // Outcome of stages that ran in the shell
+    fn outcome(&self) -> ExecOutcome {
+        match self {
+            Self::Finished(output) => *output,
+            _ => ExecOutcome::Status(0),
+        }
+    }
+
+    #[cfg(windows)]
+    fn wait(self) -> ExecOutcome {
+        let exit_status = match self {
+            Self::Process(mut child) => match child.wait() {
+                Ok(exit_status) => exit_status,
+                Err(why) => {
+                    eprintln!("Unable to execute command! {}", why);
+                    return ExecOutcome::Status(1);
+                },
             },
-            OutputType::DependNot => {
-                if last_output == 0 {
-                    continue;
-                }
+            Self::Finished(output) => return output,
+        };
+
+        exit_status.code().map_or(ExecOutcome::Status(0), ExecOutcome::Status)
+    }
+}
+
+fn run_pipeline(pipeline: Vec<BrCommand>, ctx: &mut Context) -> ExecOutcome {
+    let background = pipeline.iter().any(|cmd| cmd.background);
+    let description = describe_pipeline(&pipeline);
+    let stage_count = pipeline.len();
+
+    // One entry per stage so each stage's status is known
+    let mut stages = Vec::new();
+    let mut pgid = 0;
+    let mut stdin = Input::Inherit;
+
+    for (idx, mut cmd) in pipeline.into_iter().enumerate() {
+        let is_last = idx + 1 == stage_count;
+
+        // '.' on its own is 'source'
+        if (cmd.command.starts_with('.') && cmd.command != ".") || cmd.command.starts_with('/') {
+            let file = PathBuf::from_str(&cmd.command).unwrap();
+            if file.exists() && file.is_dir() {
+                cmd.command = String::from("cd");
+                cmd.args.insert(0, file.to_str().unwrap().to_string());
+            }
+        } else if !is_command(&cmd.command, ctx) {
+            // The directory found in CDPATH is passed on so 'cd'
+            // doesn't show it
+            if let Some(dir) = dirs::auto_cd_dir(ctx, &cmd.command) {
+                cmd.command = String::from("cd");
+                cmd.args.insert(0, dir.to_string_lossy().to_string());
+            }
+        }
+
+        let (next_stdin, mut stdout) = if is_last {
+            // Background jobs share the terminal unless disabled
+            let stdout = if background && !ctx.get_variable("BG_OUTPUT", true, false) {
+                Output::Null
+            } else {
+                Output::Inherit
+            };
+            (Input::Inherit, stdout)
+        } else {
+            match pipe() {
+                Ok((reader, writer)) => (Input::Pipe(reader), Output::Pipe(writer)),
+                Err(why) => {
+                    eprintln!("Unable to create pipe! {}", why);
+                    return ExecOutcome::Status(1);
+                },
+            }
+        };
+
+        let cur_stdin = std::mem::replace(&mut stdin, next_stdin);
+
+        // Redirections on 'exec' without a command apply to the
+        // shell itself
+        if cmd.command == "exec" && cmd.args.is_empty() && stage_count == 1 && !background {
+            let output = match open_redirects(&cmd) {
+                Ok(Some(file)) => redirect_shell_output(file),
+                Ok(None) => ExecOutcome::Status(0),
+                Err(why) => {
+                    eprintln!("{}", why);
+                    ExecOutcome::Status(1)
+                },
+            };
+
+            if !output.is_success() {
+                return output;
+            }
+
+            cmd.redirects.clear();
+        }
+
+        match open_redirects(&cmd) {
+            Ok(Some(file)) => stdout = Output::File(file),
+            Ok(None) => {},
+            Err(why) => {
+                eprintln!("{}", why);
+                stages.push(PipelineChild::Finished(ExecOutcome::Status(1)));
+                continue;
             },
-            OutputType::Redirect => {},
-            OutputType::RedirectAppend => {},
-        }
-        let mut output = 0;
-
-        match cmd.command.as_ref() {
-            "exit" => return None,
-            _ => {
-                if cmd.command.starts_with('.') || cmd.command.starts_with('/') {
-                    let file = PathBuf::from_str(&cmd.command).unwrap();
-                    if file.exists() && file.is_dir() {
-                        cmd.command = String::from("cd");
-                        cmd.args.insert(0, file.to_str().unwrap().to_string());
-                    }
-                }
+        }
 
-                for builtin in ctx.builtins.clone() {
-                    if builtin.name == cmd.command {
-                        output = (builtin.execute)(cmd.args.clone(), ctx);
+        let process_group = ProcessGroup {
+            pgid,
+            job_control: ctx.job_control,
+            foreground: !background,
+        };
 
-                        last_output = output;
-                        joiner = cmd.output_type;
+        let builtin = ctx.builtins.iter().find(|builtin| builtin.name == cmd.command).cloned();
+        if let Some(builtin) = builtin {
+            let stdout_fd = stdout.raw_fd();
+            let mut io = CommandIo {
+                stdin:  cur_stdin.into_reader(),
+                stdout: stdout.into_writer(),
+                stderr: Box::new(io::stderr()),
+            };
 
-                        continue 'cmdloop;
-                    }
-                }
+            // Builtins at the end of a foreground pipeline run in
+            // the shell so that changes to the context are kept,
+            // background ones are forked so they become a job
+            if is_last && !background {
+                // Commands run by builtins such as 'source' and
+                // 'exec' inherit the shell's stdout so point it at
+                // the builtin's output
+                let output = with_stdout(stdout_fd, || (builtin.execute)(cmd.args, ctx, &mut io));
+                io.flush();
+                stages.push(PipelineChild::Finished(output));
+                continue;
+            }
 
-                let mut external_cmd_builder = Command::new(cmd.command);
-                external_cmd_builder.args(cmd.args);
+            match run_builtin_child(builtin, cmd.args, ctx, io, stdout_fd, process_group) {
+                Ok(child) => stages.push(child),
+                Err(why) => {
+                    eprintln!("Unable to run builtin! {}", why);
+                    stages.push(PipelineChild::Finished(ExecOutcome::Status(1)));
+                },
+            }
+        } else {
+            // Taken before the output is moved into the command,
+            // which keeps it open until the end of this stage
+            let stdout_fd = stdout.raw_fd();
 
-                let stdin = last_command.map_or(Stdio::inherit(), |output: Child| Stdio::from(output.stdout.unwrap()));
-                external_cmd_builder.stdin(stdin);
+            let mut external_cmd_builder = external_command(&cmd.command, ctx);
+            external_cmd_builder
+                .args(&cmd.args)
+                .envs(ctx.exported_variables())
+                .stdin(cur_stdin.into_stdio())
+                .stdout(stdout.into_stdio());
 
-                if cmd.background {
-                    external_cmd_builder.stdout(Stdio::null());
-                } else {
-                    let stdout = if commands.peek().is_some() && cmd.output_type == OutputType::Pipe {
-                        Stdio::piped()
-                    } else {
-                        Stdio::inherit()
-                    };
+            process_group.setup_command(&mut external_cmd_builder);
 
-                    external_cmd_builder.stdout(stdout);
-                }
+            match external_cmd_builder.spawn() {
+                Ok(child) => stages.push(PipelineChild::Process(child)),
+                Err(why) if why.kind() == io::ErrorKind::NotFound && is_missing(&cmd.command, ctx) => {
+                    let output = with_stdout(stdout_fd, || command_not_found(&cmd.command, &cmd.args, ctx));
+                    stages.push(PipelineChild::Finished(output));
+                    continue;
+                },
+                Err(why) => {
+                    // Existing files can still fail with NotFound,
+                    // such as scripts with a missing interpreter
+                    eprintln!("Unable to execute '{}'! {}", cmd.command, why);
+                    let code = if why.kind() == io::ErrorKind::NotFound { 127 } else { 126 };
+                    stages.push(PipelineChild::Finished(ExecOutcome::Status(code)));
+                    continue;
+                },
+            }
+        }
+
+        #[cfg(unix)]
+        if let Some(pid) = stages.last().and_then(PipelineChild::pid) {
+            pgid = process_group.join(pid);
+        }
+    }
+
+    if background {
+        return start_background(stages, pgid, description, ctx);
+    }
+
+    let statuses = wait_pipeline(stages, pgid, description, ctx);
+    pipeline_status(statuses, ctx)
+}
+
+// Set PIPESTATUS and get the status of the whole pipeline, with
+// OPT_PIPEFAIL set this is the last stage that failed
+fn pipeline_status(statuses: Vec<ExecOutcome>, ctx: &mut Context) -> ExecOutcome {
+    let codes: Vec<String> = statuses.iter().map(|status| status.code().to_string()).collect();
+    ctx.set_variable_value("PIPESTATUS", VarValue::List(codes));
+
+    let last = statuses.last().copied().unwrap_or_default();
+    if last.is_control_flow() || !ctx.get_variable("OPT_PIPEFAIL", false, false) {
+        return last;
+    }
+
+    statuses
+        .into_iter()
+        .rev()
+        .find(|status| !status.is_success())
+        .unwrap_or(last)
+}
+
+// Track a pipeline as a background job without waiting on it
+#[cfg(unix)]
+fn start_background(stages: Vec<PipelineChild>, pgid: i32, description: String, ctx: &mut Context) -> ExecOutcome {
+    let pids: Vec<i32> = stages.iter().filter_map(PipelineChild::pid).collect();
+    if pids.is_empty() {
+        return stages.last().map(PipelineChild::outcome).unwrap_or_default();
+    }
+
+    ctx.set_variable("!", pids[pids.len() - 1], false);
+    let id = ctx.jobs.add(Job::new(pgid, description, pids));
+    eprintln!("[{}] {}", id, pgid);
+
+    ExecOutcome::Status(0)
+}
+
+#[cfg(windows)]
+fn start_background(stages: Vec<PipelineChild>, _pgid: i32, _description: String, _ctx: &mut Context) -> ExecOutcome {
+    stages.last().map(PipelineChild::outcome).unwrap_or_default()
+}
+
+// Wait for every stage of a foreground pipeline, a stopped
+// pipeline is added to the job table instead
+#[cfg(unix)]
+fn wait_pipeline(stages: Vec<PipelineChild>, pgid: i32, description: String, ctx: &mut Context) -> Vec<ExecOutcome> {
+    let pids: Vec<i32> = stages.iter().filter_map(PipelineChild::pid).collect();
+    if pids.is_empty() {
+        return stages.iter().map(PipelineChild::outcome).collect();
+    }
+
+    let mut job = Job::new(pgid, description, pids);
+    if let Err(why) = job::wait_foreground(&mut job, ctx.job_control) {
+        eprintln!("Unable to wait for pipeline! {}", why);
 
-                let external_cmd = external_cmd_builder.spawn();
-                match external_cmd {
-                    Ok(mut cmd_child) => {
-                        if cmd.output_type == OutputType::Pipe {
-                            last_command = Some(cmd_child);
-                            continue;
-                        }
-
-                        last_command = None;
-                        if cmd.background {
-                            continue;
-                        }
-
-                        match cmd_child.wait() {
-                            Ok(exit_status) => {
-                                output = get_exit_code(exit_status).unwrap_or_else(|| {
-                                    eprintln!("Status terminated with no exit status!");
-                                    0
-                                })
-                            },
-                            Err(why) => {
-                                eprintln!("Unable to execute command! {}", why);
-                            },
-                        }
-                    },
-                    Err(why) => {
-                        eprintln!("Unable to execute command! {}", why);
-                        last_command = None;
-                    },
+        return vec![ExecOutcome::Status(1)];
+    }
+
+    if job.state() == JobState::Stopped {
+        let id = ctx.jobs.add(job);
+        if let Some(job) = ctx.jobs.get(id) {
+            eprintln!("\n{}", ctx.jobs.format(job));
+        }
+
+        return vec![ExecOutcome::Status(128 + libc::SIGTSTP)];
+    }
+
+    stages
+        .iter()
+        .map(|stage| match stage.pid() {
+            Some(pid) => job
+                .processes
+                .iter()
+                .find(|process| process.pid == pid)
+                .and_then(|process| process.outcome)
+                .unwrap_or_default(),
+            None => stage.outcome(),
+        })
+        .collect()
+}
+
+#[cfg(windows)]
+fn wait_pipeline(stages: Vec<PipelineChild>, _pgid: i32, _description: String, _ctx: &mut Context) -> Vec<ExecOutcome> {
+    stages.into_iter().map(PipelineChild::wait).collect()
+}
+
+fn describe_pipeline(pipeline: &[BrCommand]) -> String {
+    pipeline
+        .iter()
+        .map(|cmd| {
+            let mut words = vec![cmd.command.clone()];
+            words.extend(cmd.args.iter().cloned());
+            for redirect in &cmd.redirects {
+                match redirect.redirect_type {
+                    RedirectType::Write => words.push(String::from(">")),
+                    RedirectType::Append => words.push(String::from(">>")),
                 }
-            },
+                words.push(redirect.target.clone());
+            }
+
+            words.iter().map(|word| quote_word(word)).collect::<Vec<String>>().join(" ")
+        })
+        .collect::<Vec<String>>()
+        .join(" | ")
+}
+
+// Quote words so the description can be read back as input
+pub(crate) fn quote_word(word: &str) -> String {
+    if !word.is_empty() && !word.contains(|ch: char| ch.is_whitespace() || ch == '"') {
+        return word.to_string();
+    }
+
+    format!("\"{}\"", word.replace('"', "\\\""))
+}
+
+// Process group that each command in a pipeline is placed in
+// when job control is enabled, a pgid of 0 means the group
+// will be created by the next command
+#[derive(Clone, Copy)]
+struct ProcessGroup {
+    #[allow(dead_code)]
+    pgid:        i32,
+    job_control: bool,
+    foreground:  bool,
+}
+
+impl ProcessGroup {
+    #[cfg(unix)]
+    fn setup_command(&self, command: &mut Command) {
+        use std::os::unix::process::CommandExt;
+
+        if self.job_control {
+            command.process_group(self.pgid);
         }
 
-        last_output = output;
-        joiner = cmd.output_type;
+        let group = *self;
+        unsafe {
+            command.pre_exec(move || {
+                group.setup_child();
+                Ok(())
+            });
+        }
     }
 
-    Some(last_output)
+    #[cfg(windows)]
+    fn setup_command(&self, _command: &mut Command) {}
+
+    // Called in the child process before running the command
+    #[cfg(unix)]
+    fn setup_child(&self) {
+        if self.job_control {
+            unsafe {
+                libc::setpgid(0, self.pgid);
+            }
+
+            if self.foreground {
+                job::give_terminal(unsafe { libc::getpgrp() });
+            }
+        }
+
+        job::reset_signals();
+    }
+
+    // Called in the shell after a child has been created to
+    // avoid racing the child, returns the group's pgid
+    #[cfg(unix)]
+    fn join(&self, pid: i32) -> i32 {
+        let pgid = if self.pgid == 0 { pid } else { self.pgid };
+        if self.job_control {
+            unsafe {
+                libc::setpgid(pid, pgid);
+            }
+
+            if self.foreground {
+                job::give_terminal(pgid);
+            }
+        }
+
+        pgid
+    }
+}
+
+#[cfg(unix)]
+fn redirect_shell_output(file: File) -> ExecOutcome {
+    let _ = io::stdout().flush();
+    if unsafe { libc::dup2(file.as_raw_fd(), libc::STDOUT_FILENO) } == -1 {
+        eprintln!("Unable to redirect output! {}", io::Error::last_os_error());
+        return ExecOutcome::Status(1);
+    }
+
+    ExecOutcome::Status(0)
 }
 
+// Point the shell's stdout at fd while f runs so that commands
+// run from within the shell write to the stage's output
 #[cfg(unix)]
-fn get_exit_code(exit_status: ExitStatus) -> Option<i32> {
-    match exit_status.code() {
-        Some(code) => {
-            return Some(code);
+fn with_stdout<T>(fd: Option<RawFd>, f: impl FnOnce() -> T) -> T {
+    let fd = match fd {
+        Some(fd) => fd,
+        None => return f(),
+    };
+
+    let _ = io::stdout().flush();
+
+    // Keep the shell's stdout out of any child processes
+    let saved = unsafe { libc::fcntl(libc::STDOUT_FILENO, libc::F_DUPFD_CLOEXEC, 3) };
+    if saved == -1 || unsafe { libc::dup2(fd, libc::STDOUT_FILENO) } == -1 {
+        eprintln!("Unable to redirect output! {}", io::Error::last_os_error());
+        if saved != -1 {
+            unsafe { libc::close(saved) };
+        }
+
+        return f();
+    }
+
+    let output = f();
+
+    let _ = io::stdout().flush();
+    unsafe {
+        libc::dup2(saved, libc::STDOUT_FILENO);
+        libc::close(saved);
+    }
+
+    output
+}
+
+#[cfg(windows)]
+fn with_stdout<T>(_fd: Option<RawFd>, f: impl FnOnce() -> T) -> T {
+    f()
+}
+
+#[cfg(windows)]
+fn redirect_shell_output(_file: File) -> ExecOutcome {
+    eprintln!("Unable to redirect output! Not supported on Windows");
+
+    ExecOutcome::Status(1)
+}
+
+// Use the cached path for commands found on PATH so it isn't
+// searched again
+fn external_command(name: &str, ctx: &mut Context) -> Command {
+    let path = if name.contains('/') {
+        None
+    } else {
+        ctx.commands.borrow_mut().lookup_hit(name)
+    };
+
+    match path {
+        Some(path) => {
+            let mut command = Command::new(path);
+
+            #[cfg(unix)]
+            {
+                use std::os::unix::process::CommandExt;
+                command.arg0(name);
+            }
+
+            command
         },
-        None => match exit_status.signal() {
-            Some(signal) => {
-                return Some(128 + signal);
-            },
-            None => return None,
+        None => Command::new(name),
+    }
+}
+
+// Paths that don't exist and names that aren't on PATH
+fn is_missing(name: &str, ctx: &Context) -> bool {
+    if name.contains('/') {
+        return !Path::new(name).exists();
+    }
+
+    ctx.commands.borrow_mut().lookup(name).is_none()
+}
+
+fn open_redirects(cmd: &BrCommand) -> Result<Option<File>, String> {
+    let mut output = None;
+
+    // Every file is opened so they're all created, matching
+    // other shells, but only the last receives output
+    for redirect in &cmd.redirects {
+        let mut options = OpenOptions::new();
+        match redirect.redirect_type {
+            RedirectType::Write => options.write(true).create(true).truncate(true),
+            RedirectType::Append => options.append(true).create(true),
+        };
+
+        match options.open(&redirect.target) {
+            Ok(file) => output = Some(file),
+            Err(why) => return Err(format!("Unable to open '{}'! {}", redirect.target, why)),
+        }
+    }
+
+    Ok(output)
+}
+
+#[cfg(unix)]
+fn run_builtin_child(
+    builtin: BrBuiltin,
+    args: Vec<String>,
+    ctx: &mut Context,
+    mut io: CommandIo,
+    stdout_fd: Option<RawFd>,
+    process_group: ProcessGroup,
+) -> io::Result<PipelineChild> {
+    // Flush any pending output so it isn't duplicated in the
+    // child process
+    let _ = io::stdout().flush();
+
+    match unsafe { libc::fork() } {
+        -1 => Err(io::Error::last_os_error()),
+        0 => {
+            process_group.setup_child();
+
+            let output = with_stdout(stdout_fd, || (builtin.execute)(args, ctx, &mut io));
+            io.flush();
+
+            unsafe { libc::_exit(output.code()) }
         },
+        pid => Ok(PipelineChild::Forked(pid)),
     }
 }
 
 #[cfg(windows)]
-fn get_exit_code(exit_status: ExitStatus) -> Option<i32> {
-    exit_status.code()
+fn run_builtin_child(
+    builtin: BrBuiltin,
+    args: Vec<String>,
+    ctx: &mut Context,
+    mut io: CommandIo,
+    _stdout_fd: Option<RawFd>,
+    _process_group: ProcessGroup,
+) -> io::Result<PipelineChild> {
+    // No fork on Windows so run in place, large outputs may
+    // block if the pipe fills before the next command reads
+    let output = (builtin.execute)(args, ctx, &mut io);
+    io.flush();
+
+    Ok(PipelineChild::Finished(output))
 }
@@ -0,0 +1,89 @@
+use br_data::{command::ExecOutcome, context::Context};
+use br_parser::get_valid_commands;
+
+use crate::{execute, quote_word};
+
+// Alias run in place of commands that can't be found
+const HOOK_NAME: &str = "command_not_found";
+
+pub fn command_not_found(name: &str, args: &[String], ctx: &mut Context) -> ExecOutcome {
+    if ctx.aliases.contains_key(HOOK_NAME) && !ctx.in_not_found_hook {
+        ctx.in_not_found_hook = true;
+
+        let mut words = vec![HOOK_NAME.to_string(), quote_word(name)];
+        words.extend(args.iter().map(|arg| quote_word(arg)));
+
+        let buffer = std::mem::replace(&mut ctx.cli.command_buffer, words.join(" "));
+        let output = execute(ctx);
+        ctx.cli.command_buffer = buffer;
+
+        ctx.in_not_found_hook = false;
+
+        return output;
+    }
+
+    eprintln!("Unknown command '{}'!", name);
+
+    let suggestions = suggest(name, ctx);
+    if !suggestions.is_empty() {
+        let suggestions: Vec<String> = suggestions.iter().map(|cmd| format!("'{}'", cmd)).collect();
+        eprintln!("Did you mean {}?", suggestions.join(", "));
+    }
+
+    ExecOutcome::Status(127)
+}
+
+// Closest commands by edit distance, at most three are returned
+fn suggest(name: &str, ctx: &Context) -> Vec<String> {
+    let max_distance = (name.chars().count() / 3).clamp(1, 3);
+
+    let mut matches: Vec<(usize, String)> = get_valid_commands(ctx)
+        .into_iter()
+        .map(|cmd| (edit_distance(name, &cmd), cmd))
+        .filter(|(distance, _)| *distance <= max_distance)
+        .collect();
+    matches.sort();
+    matches.dedup();
+
+    matches.into_iter().take(3).map(|(_, cmd)| cmd).collect()
+}
+
+// Levenshtein distance between two strings
+fn edit_distance(a: &str, b: &str) -> usize {
+    let b: Vec<char> = b.chars().collect();
+    let mut prev: Vec<usize> = (0..=b.len()).collect();
+
+    for (i, a_ch) in a.chars().enumerate() {
+        let mut cur = vec![i + 1; b.len() + 1];
+        for (j, b_ch) in b.iter().enumerate() {
+            let cost = if a_ch == *b_ch { 0 } else { 1 };
+            cur[j + 1] = (prev[j] + cost).min(prev[j + 1] + 1).min(cur[j] + 1);
+        }
+        prev = cur;
+    }
+
+    prev[b.len()]
+}
+
+// Make cargo stop complaining about functions used for
+// tests
+#[allow(unused_imports, dead_code)]
+mod test {
+    use super::edit_distance;
+
+    #[test]
+    fn distance() {
+        assert_eq!(edit_distance("", ""), 0);
+        assert_eq!(edit_distance("ls", "ls"), 0);
+        assert_eq!(edit_distance("", "git"), 3);
+        assert_eq!(edit_distance("gti", "git"), 2);
+        assert_eq!(edit_distance("gitt", "git"), 1);
+        assert_eq!(edit_distance("kitten", "sitting"), 3);
+    }
+
+    #[test]
+    fn distance_unicode() {
+        assert_eq!(edit_distance("café", "cafe"), 1);
+        assert_eq!(edit_distance("…", "..."), 3);
+    }
+}
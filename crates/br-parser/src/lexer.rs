@@ -6,15 +6,37 @@ fn parse_var(lex: &mut Lexer<Token>) -> Option<(String, bool)> {
     // Trim "$" from start
     let var = lex.slice()[1..].to_string();
 
+    // Keep the '#' of length lookups before the name
+    let (prefix, var) = match var.strip_prefix('#') {
+        Some(var) => ("#", var.to_string()),
+        None => ("", var),
+    };
+
     let var_name = if var.starts_with("ENV:") {
-        (var.strip_prefix("ENV:").unwrap().to_string(), true)
+        (prefix.to_string() + var.strip_prefix("ENV:").unwrap(), true)
     } else {
-        (var, false)
+        (prefix.to_string() + &var, false)
     };
 
     Some(var_name)
 }
 
+// Trim the quotes and unescape '\\' and '\"', other escapes such
+// as '\n' are kept for the command to handle
+fn parse_string(lex: &mut Lexer<Token>) -> String {
+    let slice = lex.slice();
+    let mut value = String::new();
+    let mut chars = slice[1..slice.len() - 1].chars().peekable();
+    while let Some(ch) = chars.next() {
+        match chars.next_if(|next| ch == '\\' && (*next == '\\' || *next == '"')) {
+            Some(next) => value.push(next),
+            None => value.push(ch),
+        }
+    }
+
+    value
+}
+
 impl From<&mut Lexer<'_, Token>> for OutputType {
     fn from(lex: &mut Lexer<Token>) -> Self {
         match lex.slice() {
@@ -28,15 +50,15 @@ impl From<&mut Lexer<'_, Token>> for OutputType {
     }
 }
 
-#[derive(Logos, Debug, PartialEq, Clone, Eq, Hash)]
+#[derive(Logos, Debug, Default, PartialEq, Clone, Eq, Hash)]
 pub enum Token {
     #[regex("#.*")]
     Comment,
-    #[regex("[a-zA-Z0-9/_\\-:\\.~]+")]
+    #[regex("\\\\?[a-zA-Z0-9/_\\-:\\.~%+?@]+")]
     Word,
     #[regex(" +")]
     Whitespace,
-    #[regex(r#""([^"\\]|\\r|\\t|\\u|\\n|\\")*""#, callback = |lex| lex.slice()[1..lex.slice().len() - 1].to_string())]
+    #[regex(r#""([^"\\]|\\r|\\t|\\u|\\n|\\"|\\\\)*""#, callback = parse_string)]
     StringLiteral(String),
     #[regex("[0-9]+", priority = 2)]
     NumberLiteral,
@@ -44,19 +66,16 @@ pub enum Token {
     Background,
     #[regex("(;|\\|\\||\\||&&|>>|>)", callback = |lex| OutputType::from(lex))]
     Output(OutputType),
-    #[regex("\\$ENV:[a-zA-Z0-9_]+", priority = 2, callback = parse_var)]
-    #[regex("\\$[a-zA-Z0-9_]+", callback = parse_var)]
+    #[regex("\\$#?ENV:[a-zA-Z0-9_]+(\\[[^\\]\\s]*\\])?", priority = 2, callback = parse_var)]
+    #[regex("\\$#?[a-zA-Z0-9_]+(\\[[^\\]\\s]*\\])?", callback = parse_var)]
+    #[token("$?", callback = parse_var)]
+    #[token("$!", callback = parse_var)]
     Variable((String, bool)),
+    #[default]
     #[error]
     Error,
 }
 
-impl Default for Token {
-    fn default() -> Self {
-        Token::Error
-    }
-}
-
 // Make cargo stop complaining about functions used for
 // tests
 #[allow(unused_imports, dead_code)]
@@ -94,6 +113,35 @@ mod test {
         ])
     }
 
+    #[test]
+    fn escaped_word() {
+        assert_seq("\\ls -a", vec![
+            (Word, 0..3, "\\ls"),
+            (Whitespace, 3..4, " "),
+            (Word, 4..6, "-a"),
+        ])
+    }
+
+    #[test]
+    fn list_variables() {
+        assert_seq("echo $#PATHS $PATHS[2..]", vec![
+            (Word, 0..4, "echo"),
+            (Whitespace, 4..5, " "),
+            (Variable((String::from("#PATHS"), false)), 5..12, "$#PATHS"),
+            (Whitespace, 12..13, " "),
+            (Variable((String::from("PATHS[2..]"), false)), 13..24, "$PATHS[2..]"),
+        ])
+    }
+
+    #[test]
+    fn last_status() {
+        assert_seq("echo $?", vec![
+            (Word, 0..4, "echo"),
+            (Whitespace, 4..5, " "),
+            (Variable((String::from("?"), false)), 5..7, "$?"),
+        ])
+    }
+
     #[test]
     fn piping() {
         assert_seq("> | >> || && ;", vec![
@@ -110,4 +158,16 @@ mod test {
             (Output(OutputType::Ignore), 13..14, ";"),
         ])
     }
+
+    #[test]
+    fn string_escapes() {
+        assert_seq(r#"echo "a \"b\" \\ \n""#, vec![
+            (Word, 0..4, "echo"),
+            (Whitespace, 4..5, " "),
+            (StringLiteral(String::from(r#"a "b" \ \n"#)), 5..20, r#""a \"b\" \\ \n""#),
+        ]);
+
+        let mut lexer = Token::lexer(r#""\d""#);
+        assert_eq!(lexer.next(), Some(Error));
+    }
 }
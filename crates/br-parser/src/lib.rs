@@ -1,8 +1,7 @@
-#[cfg(unix)]
-use std::os::unix::prelude::MetadataExt;
-use std::{env, fs::Metadata, path::PathBuf, str::FromStr};
+use std::{path::PathBuf, str::FromStr};
 
-use br_data::context::Context;
+pub use br_data::cache::can_exec;
+use br_data::{context::Context, dirs};
 use lexer::Token;
 use logos::Logos;
 use parser::{parse_lex, CommandList, ParseError};
@@ -10,8 +9,9 @@ use parser::{parse_lex, CommandList, ParseError};
 pub mod lexer;
 pub mod parser;
 
-#[derive(Debug, PartialEq, Clone, Eq, Hash)]
+#[derive(Debug, Default, PartialEq, Clone, Eq, Hash)]
 pub enum OutputType {
+    #[default]
     Ignore,
     Pipe,
     Depend,
@@ -20,18 +20,36 @@ pub enum OutputType {
     RedirectAppend,
 }
 
-impl Default for OutputType {
-    fn default() -> Self {
-        Self::Ignore
-    }
+#[derive(Debug, PartialEq, Clone, Eq, Hash)]
+pub enum RedirectType {
+    Write,
+    Append,
+}
+
+#[derive(Debug, PartialEq, Clone, Eq, Hash)]
+pub struct Redirect {
+    pub redirect_type: RedirectType,
+    pub target:        String,
 }
 
 #[derive(Debug, Default, PartialEq, Clone, Eq, Hash)]
 pub struct Command {
     pub command:     String,
     pub args:        Vec<String>,
+    pub redirects:   Vec<Redirect>,
     pub background:  bool,
     pub output_type: OutputType,
+    pub escaped:     bool,
+}
+
+#[derive(Debug, PartialEq, Clone, Copy, Eq, Hash)]
+pub enum Continuation {
+    // Line ends with '\', join the next line directly
+    Escape,
+    // Line ends with a joiner such as '|' or '&&'
+    Operator,
+    // String literal hasn't been closed
+    Quote,
 }
 
 pub fn parse_command(command: String, ctx: &Context) -> Result<CommandList, ParseError> {
@@ -39,19 +57,36 @@ pub fn parse_command(command: String, ctx: &Context) -> Result<CommandList, Pars
     parse_lex(lex, ctx)
 }
 
-#[cfg(unix)]
-pub fn can_exec(md: Metadata) -> bool {
-    let mode = md.mode();
-    mode & 0o001 == 0o001
-}
+pub fn needs_continuation(command: &str) -> Option<Continuation> {
+    let mut lex = Token::lexer(command);
+    let mut last_token = None;
+    while let Some(token) = lex.next() {
+        match token {
+            Token::Error if lex.slice().starts_with('"') => return Some(Continuation::Quote),
+            Token::Whitespace | Token::Comment => {},
+            _ => last_token = Some(token),
+        }
+    }
 
-#[cfg(windows)]
-pub fn can_exec(md: Metadata) -> bool {
-    // TODO: Windows perms
-    true
+    if command.ends_with('\\') {
+        return Some(Continuation::Escape);
+    }
+
+    match last_token {
+        Some(Token::Output(OutputType::Pipe))
+        | Some(Token::Output(OutputType::Depend))
+        | Some(Token::Output(OutputType::DependNot)) => Some(Continuation::Operator),
+        _ => None,
+    }
 }
 
 pub fn is_valid_command(command: &str, ctx: &Context) -> bool {
+    // Escaped commands skip alias expansion
+    let (command, check_aliases) = match command.strip_prefix('\\') {
+        Some(command) => (command, false),
+        None => (command, true),
+    };
+
     if command.starts_with('.') || command.starts_with('/') {
         let file = PathBuf::from_str(command).unwrap();
         if file.exists() {
@@ -63,37 +98,16 @@ pub fn is_valid_command(command: &str, ctx: &Context) -> bool {
         }
     }
 
-    if ctx.builtins.clone().into_iter().any(|b| b.name == command) || ctx.aliases.contains_key(command) {
+    let is_alias = check_aliases && ctx.aliases.contains_key(command);
+    if ctx.builtins.clone().into_iter().any(|b| b.name == command) || is_alias {
         return true;
     }
 
-    let paths = match env::var("PATH") {
-        Ok(paths) => paths,
-        Err(_) => return false,
-    };
-
-    let paths = env::split_paths(&paths);
-
-    for path in paths {
-        let files = if let Ok(files) = path.read_dir() {
-            files
-        } else {
-            continue;
-        };
-
-        for file in files.flatten() {
-            if file.file_name() != command {
-                continue;
-            }
-            if let Ok(metadata) = file.metadata() {
-                if can_exec(metadata) && file.file_name() == command {
-                    return true;
-                }
-            }
-        }
+    if ctx.commands.borrow_mut().lookup(command).is_some() {
+        return true;
     }
 
-    false
+    dirs::auto_cd_dir(ctx, command).is_some()
 }
 
 pub fn get_valid_commands(ctx: &Context) -> Vec<String> {
@@ -105,32 +119,27 @@ pub fn get_valid_commands(ctx: &Context) -> Vec<String> {
     let mut aliases: Vec<String> = ctx.aliases.keys().map(|alias| alias.to_string()).collect();
     cmds.append(&mut aliases);
 
-    let paths = match env::var("PATH") {
-        Ok(paths) => paths,
-        Err(_) => return cmds,
-    };
-
-    let paths = env::split_paths(&paths);
-
-    for path in paths {
-        let files = if let Ok(files) = path.read_dir() {
-            files
-        } else {
-            continue;
-        };
-
-        for file in files.flatten() {
-            if let Ok(metadata) = file.metadata() {
-                if can_exec(metadata) {
-                    if let Some(file_name) = file.file_name().to_str() {
-                        cmds.push(file_name.to_string())
-                    }
-                }
-            }
-        }
-    }
+    let mut commands = ctx.commands.borrow_mut().names();
+    cmds.append(&mut commands);
 
     cmds.sort();
 
     cmds
 }
+
+// Make cargo stop complaining about functions used for
+// tests
+#[allow(unused_imports, dead_code)]
+mod test {
+    use super::{needs_continuation, Continuation};
+
+    #[test]
+    fn continuation() {
+        assert_eq!(needs_continuation("echo hi"), None);
+        assert_eq!(needs_continuation("echo hi \\"), Some(Continuation::Escape));
+        assert_eq!(needs_continuation("echo hi |"), Some(Continuation::Operator));
+        assert_eq!(needs_continuation("echo hi && # comment"), Some(Continuation::Operator));
+        assert_eq!(needs_continuation("echo \"hi"), Some(Continuation::Quote));
+        assert_eq!(needs_continuation("echo hi;"), None);
+    }
+}
@@ -1,9 +1,9 @@
 use std::{fmt, ops::Range};
 
-use br_data::context::Context;
+use br_data::{context::Context, variable::VarValue};
 use logos::Lexer;
 
-use crate::{lexer::Token, Command};
+use crate::{lexer::Token, Command, OutputType, Redirect, RedirectType};
 
 pub type CommandList = Vec<Command>;
 
@@ -11,6 +11,7 @@ pub type CommandList = Vec<Command>;
 pub enum ParseError {
     UnexpectedValue(Range<usize>, String, String),
     LexError(Range<usize>, String),
+    UnsetVariable(Range<usize>, String),
 }
 
 impl fmt::Display for ParseError {
@@ -24,6 +25,10 @@ impl fmt::Display for ParseError {
                 "Unable to parse input at pos {}..{}, unexpected value '{}'",
                 range.start, range.end, value,
             ),
+            Self::UnsetVariable(range, name) => format!(
+                "Variable '{}' at pos {}..{} is unset",
+                name, range.start, range.end,
+            ),
         };
         write!(f, "{}", value)
     }
@@ -34,6 +39,7 @@ pub fn parse_lex(mut lex: Lexer<Token>, ctx: &Context) -> Result<CommandList, Pa
 
     let mut arg_builder = String::new();
     let mut cmd_builder = Command::default();
+    let mut redirect = None;
 
     let mut last_token = None;
     while let Some(token) = lex.next() {
@@ -57,12 +63,56 @@ pub fn parse_lex(mut lex: Lexer<Token>, ctx: &Context) -> Result<CommandList, Pa
             },
             Token::Error => return Err(ParseError::LexError(lex.span(), lex.slice().to_string())),
             Token::Variable((var_name, is_env)) => {
-                let var_val = ctx.get_variable(&var_name, String::default(), is_env);
-                arg_builder.push_str(&var_val);
+                let (name, index) = match var_name.strip_suffix(']').and_then(|name| name.split_once('[')) {
+                    Some((name, index)) => (name, Some(index)),
+                    None => (var_name.as_str(), None),
+                };
+                let (name, length) = match name.strip_prefix('#') {
+                    Some(name) => (name, true),
+                    None => (name, false),
+                };
+
+                if !ctx.has_variable(name, is_env) && ctx.get_variable("OPT_NOUNSET", false, false) {
+                    return Err(ParseError::UnsetVariable(lex.span(), name.to_string()));
+                }
+
+                let value = ctx.get_variable_value(name, is_env).unwrap_or_default();
+
+                // Environment variables are indexed as ':' separated
+                // lists, the same as PATH
+                let value = match (value, is_env && index.is_some()) {
+                    (VarValue::Str(value), true) => VarValue::List(value.split(':').map(String::from).collect()),
+                    (value, _) => value,
+                };
+
+                let items = match index {
+                    _ if length => vec![value.len().to_string()],
+                    Some(index) => value.index(index),
+                    None => value.items(),
+                };
+
+                // Each item becomes its own argument, the first and
+                // last join with any surrounding text
+                for (idx, item) in items.iter().enumerate() {
+                    if idx > 0 {
+                        push_arg(&mut cmd_builder, &mut arg_builder, &mut redirect);
+                    }
+
+                    arg_builder.push_str(item);
+                }
             },
             Token::Word => {
-                let word = lex.slice();
-                let arg = if word.starts_with('~') {
+                // A leading '\' stops the word from being expanded
+                let (word, escaped) = match lex.slice().strip_prefix('\\') {
+                    Some(word) => (word, true),
+                    None => (lex.slice(), false),
+                };
+
+                if escaped && cmd_builder.command.is_empty() && arg_builder.is_empty() && redirect.is_none() {
+                    cmd_builder.escaped = true;
+                }
+
+                let arg = if !escaped && word.starts_with('~') {
                     let home = if word.starts_with("~/") || word == "~" {
                         let home_dir = match home::home_dir() {
                             Some(home_dir) => home_dir.to_string_lossy().to_string(),
@@ -72,10 +122,12 @@ pub fn parse_lex(mut lex: Lexer<Token>, ctx: &Context) -> Result<CommandList, Pa
                         home_dir + word.strip_prefix("~").unwrap()
                     } else {
                         // TODO: Get other user home dir
-                        word.to_string()
+                        ctx.bookmarks.expand(word).unwrap_or_else(|| word.to_string())
                     };
 
                     home
+                } else if !escaped && word.starts_with('@') && arg_builder.is_empty() {
+                    ctx.bookmarks.expand(word).unwrap_or_else(|| word.to_string())
                 } else {
                     word.to_string()
                 };
@@ -85,13 +137,15 @@ pub fn parse_lex(mut lex: Lexer<Token>, ctx: &Context) -> Result<CommandList, Pa
             Token::NumberLiteral => arg_builder.push_str(lex.slice()),
             Token::StringLiteral(val) => arg_builder.push_str(&val),
             Token::Output(out_type) => {
+                push_arg(&mut cmd_builder, &mut arg_builder, &mut redirect);
+
                 if let Some(Token::Output(_)) = last_token {
                     return Err(ParseError::UnexpectedValue(
                         lex.span(),
                         String::from("command"),
                         lex.slice().to_string(),
                     ));
-                } else if last_token == None || cmd_builder.command.is_empty() {
+                } else if last_token.is_none() || cmd_builder.command.is_empty() {
                     return Err(ParseError::UnexpectedValue(
                         lex.span(),
                         String::from("command"),
@@ -99,34 +153,40 @@ pub fn parse_lex(mut lex: Lexer<Token>, ctx: &Context) -> Result<CommandList, Pa
                     ));
                 }
 
-                cmd_builder.output_type = out_type;
+                if redirect.is_some() {
+                    return Err(ParseError::UnexpectedValue(
+                        lex.span(),
+                        String::from("file"),
+                        lex.slice().to_string(),
+                    ));
+                }
 
-                cmd_list.push(cmd_builder);
-                cmd_builder = Command::default();
-            },
-            Token::Whitespace => {
-                if !arg_builder.is_empty() {
-                    if cmd_builder.command.is_empty() {
-                        cmd_builder.command = arg_builder;
-                    } else {
-                        cmd_builder.args.push(arg_builder);
-                    }
+                match out_type {
+                    OutputType::Redirect => redirect = Some(RedirectType::Write),
+                    OutputType::RedirectAppend => redirect = Some(RedirectType::Append),
+                    _ => {
+                        cmd_builder.output_type = out_type;
 
-                    arg_builder = String::new();
+                        cmd_list.push(cmd_builder);
+                        cmd_builder = Command::default();
+                    },
                 }
             },
+            Token::Whitespace => push_arg(&mut cmd_builder, &mut arg_builder, &mut redirect),
             Token::Comment => {},
         }
 
         last_token = Some(token);
     }
 
-    if !arg_builder.is_empty() {
-        if cmd_builder.command.is_empty() {
-            cmd_builder.command = arg_builder;
-        } else {
-            cmd_builder.args.push(arg_builder);
-        }
+    push_arg(&mut cmd_builder, &mut arg_builder, &mut redirect);
+
+    if redirect.is_some() {
+        return Err(ParseError::UnexpectedValue(
+            lex.span(),
+            String::from("file"),
+            String::from("end of input"),
+        ));
     }
 
     if !cmd_builder.command.is_empty() {
@@ -136,15 +196,33 @@ pub fn parse_lex(mut lex: Lexer<Token>, ctx: &Context) -> Result<CommandList, Pa
     Ok(cmd_list)
 }
 
+fn push_arg(cmd_builder: &mut Command, arg_builder: &mut String, redirect: &mut Option<RedirectType>) {
+    if arg_builder.is_empty() {
+        return;
+    }
+
+    let arg = std::mem::take(arg_builder);
+    if let Some(redirect_type) = redirect.take() {
+        cmd_builder.redirects.push(Redirect {
+            redirect_type,
+            target: arg,
+        });
+    } else if cmd_builder.command.is_empty() {
+        cmd_builder.command = arg;
+    } else {
+        cmd_builder.args.push(arg);
+    }
+}
+
 // Make cargo stop complaining about functions used for
 // tests
 #[allow(unused_imports, dead_code)]
 mod test {
-    use br_data::context::Context;
+    use br_data::{context::Context, variable::VarValue};
     use logos::Logos;
 
     use super::{parse_lex, ParseError};
-    use crate::{lexer::Token, Command, OutputType};
+    use crate::{lexer::Token, Command, OutputType, Redirect, RedirectType};
 
     fn get_output(command: &str) -> Result<Vec<Command>, ParseError> {
         let lex = Token::lexer(command);
@@ -159,8 +237,10 @@ mod test {
             Ok(vec![Command {
                 command:     String::from("echo"),
                 args:        vec![String::from("hi"),],
+                redirects:   Vec::new(),
                 background:  false,
                 output_type: OutputType::Ignore,
+                escaped:     false,
             },])
         );
     }
@@ -173,14 +253,18 @@ mod test {
                 Command {
                     command:     String::from("echo"),
                     args:        vec![String::from("This"),],
+                    redirects:   Vec::new(),
                     background:  false,
                     output_type: OutputType::DependNot,
+                    escaped:     false,
                 },
                 Command {
                     command:     String::from("echo"),
                     args:        vec![String::from("Not this"),],
+                    redirects:   Vec::new(),
                     background:  false,
                     output_type: OutputType::Ignore,
+                    escaped:     false,
                 },
             ])
         );
@@ -189,21 +273,142 @@ mod test {
     #[test]
     fn pipe_depend() {
         assert_eq!(
-            get_output("echo This || echo \"And this\""),
+            get_output("echo This && echo \"And this\""),
             Ok(vec![
                 Command {
                     command:     String::from("echo"),
                     args:        vec![String::from("This"),],
+                    redirects:   Vec::new(),
                     background:  false,
                     output_type: OutputType::Depend,
+                    escaped:     false,
                 },
                 Command {
                     command:     String::from("echo"),
                     args:        vec![String::from("And this"),],
+                    redirects:   Vec::new(),
                     background:  false,
                     output_type: OutputType::Ignore,
+                    escaped:     false,
                 },
             ])
         );
     }
+
+    #[test]
+    fn no_trailing_whitespace() {
+        assert_eq!(
+            get_output("echo \"Exit code\"; echo $?"),
+            Ok(vec![
+                Command {
+                    command:     String::from("echo"),
+                    args:        vec![String::from("Exit code"),],
+                    redirects:   Vec::new(),
+                    background:  false,
+                    output_type: OutputType::Ignore,
+                    escaped:     false,
+                },
+                Command {
+                    command:     String::from("echo"),
+                    args:        vec![String::from("0"),],
+                    redirects:   Vec::new(),
+                    background:  false,
+                    output_type: OutputType::Ignore,
+                    escaped:     false,
+                },
+            ])
+        );
+    }
+
+    #[test]
+    fn redirect() {
+        assert_eq!(
+            get_output("get PROMPT > prompt.txt | cat >>log"),
+            Ok(vec![
+                Command {
+                    command:     String::from("get"),
+                    args:        vec![String::from("PROMPT"),],
+                    redirects:   vec![Redirect {
+                        redirect_type: RedirectType::Write,
+                        target:        String::from("prompt.txt"),
+                    },],
+                    background:  false,
+                    output_type: OutputType::Pipe,
+                    escaped:     false,
+                },
+                Command {
+                    command:     String::from("cat"),
+                    args:        Vec::new(),
+                    redirects:   vec![Redirect {
+                        redirect_type: RedirectType::Append,
+                        target:        String::from("log"),
+                    },],
+                    background:  false,
+                    output_type: OutputType::Ignore,
+                    escaped:     false,
+                },
+            ])
+        );
+
+        assert!(get_output("echo hi >").is_err());
+        assert!(get_output("echo hi > | cat").is_err());
+    }
+
+    #[test]
+    fn list_variable() {
+        let mut ctx = Context::default();
+        ctx.set_variable_value(
+            "L",
+            VarValue::List(vec![String::from("a"), String::from("b"), String::from("c")]),
+        );
+
+        assert_eq!(
+            parse_lex(Token::lexer("echo x$L[2..] $#L"), &ctx),
+            Ok(vec![Command {
+                command:     String::from("echo"),
+                args:        vec![String::from("xb"), String::from("c"), String::from("3"),],
+                redirects:   Vec::new(),
+                background:  false,
+                output_type: OutputType::Ignore,
+                escaped:     false,
+            },])
+        );
+    }
+
+    #[test]
+    fn bookmark() {
+        let mut ctx = Context::default();
+        ctx.bookmarks.marks.insert(String::from("proj"), String::from("/work/proj"));
+
+        assert_eq!(
+            parse_lex(Token::lexer("ls @proj/src ~proj user@proj"), &ctx),
+            Ok(vec![Command {
+                command:     String::from("ls"),
+                args:        vec![
+                    String::from("/work/proj/src"),
+                    String::from("/work/proj"),
+                    String::from("user@proj"),
+                ],
+                redirects:   Vec::new(),
+                background:  false,
+                output_type: OutputType::Ignore,
+                escaped:     false,
+            },])
+        );
+    }
+
+    #[test]
+    fn escaped() {
+        assert_eq!(
+            get_output("\\ls \\~"),
+            Ok(vec![Command {
+                command:     String::from("ls"),
+                args:        vec![String::from("~"),],
+                redirects:   Vec::new(),
+                background:  false,
+                output_type: OutputType::Ignore,
+                escaped:     true,
+            },])
+        );
+    }
 }
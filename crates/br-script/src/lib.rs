@@ -1,70 +1,208 @@
 use std::{
     fs::{create_dir_all, OpenOptions},
-    io::{Read, Write},
+    io::{self, BufRead, Read, Write},
     path::{Path, PathBuf},
 };
 
-use br_data::{context::Context, get_config_dir};
-use br_executer::execute;
+use br_data::{bookmark::Bookmarks, command::ExecOutcome, context::Context, get_config_dir};
+use br_executer::{execute, run_exit_handlers};
+use br_parser::{needs_continuation, Continuation};
 
-pub fn load_rc(ctx: &mut Context) {
+mod source;
+
+// Builtins that run scripts live here as they need the executer
+pub fn load_builtins(ctx: &mut Context) {
+    br_command::load_builtins(ctx);
+    ctx.builtins.extend(vec![source::CMD, source::DOT_CMD]);
+}
+
+// Context for a new shell with the builtins and saved state
+// from the config dir loaded
+pub fn init_context() -> Context {
+    let mut ctx = Context::default();
+    load_builtins(&mut ctx);
+    ctx.bookmarks = Bookmarks::load();
+
+    ctx
+}
+
+pub fn execute_once(command: String) -> i32 {
+    let mut ctx = init_context();
+    ctx.cli.command_buffer = command;
+
+    let output = execute(&mut ctx);
+    run_exit_handlers(&mut ctx);
+
+    output.code()
+}
+
+pub fn load_rc(ctx: &mut Context) -> ExecOutcome {
     let config_dir = match get_config_dir() {
         Some(dir) => {
             let path = Path::new(&dir);
             if !path.exists() {
                 if let Err(why) = create_dir_all(path) {
                     eprintln!("Unable to create config directory! {}", why);
-                    return;
+                    return ExecOutcome::Status(1);
                 };
             }
             path.join("branshrc.br")
         },
         None => {
             eprintln!("Unable to get config directory!");
-            return;
+            return ExecOutcome::Status(1);
         },
     };
 
     if !config_dir.exists() {
         write_default_config(config_dir);
-        return;
+        return ExecOutcome::Status(0);
     }
 
-    let mut config_file = match OpenOptions::new().read(true).open(config_dir) {
+    let config = match read_file(&config_dir) {
         Ok(config) => config,
         Err(why) => {
             eprintln!("Unable to read branshrc.br! {}", why);
-            return;
+            return ExecOutcome::Status(1);
         },
     };
 
-    let mut config = String::new();
-    match config_file.read_to_string(&mut config) {
-        Ok(_) => {},
+    run_script(ctx, config.as_bytes(), "branshrc.br", true)
+}
+
+pub fn execute_file(path: String, args: Vec<String>) -> i32 {
+    let mut ctx = init_context();
+
+    let exit_code = run_file(&mut ctx, path, args);
+    run_exit_handlers(&mut ctx);
+
+    exit_code
+}
+
+pub fn run_file(ctx: &mut Context, path: String, args: Vec<String>) -> i32 {
+    let script = match read_file(Path::new(&path)) {
+        Ok(script) => script,
         Err(why) => {
-            eprintln!("Unable to read branshrc.br! {}", why);
-            return;
+            eprintln!("Unable to read script '{}'! {}", path, why);
+            return 127;
         },
     };
 
-    for (line_num, line) in config.lines().enumerate() {
-        ctx.cli.command_buffer = line.to_string();
-        if let Some(exit_code) = execute(ctx) {
-            if exit_code != 0 {
-                eprintln!("Non 0 exit code returned while running file!");
-                eprintln!("Line {}: '{}'", line_num, line);
+    set_positional_args(ctx, path.clone(), args);
 
-                return;
-            }
-        } else {
-            // 'exit' executed
-            return;
+    run_script(ctx, script.as_bytes(), &path, false).code()
+}
+
+pub fn execute_stdin(args: Vec<String>) -> i32 {
+    let mut ctx = init_context();
+
+    set_positional_args(&mut ctx, String::from("bransh"), args);
+
+    let stdin = io::stdin();
+    let reader = stdin.lock();
+    let exit_code = run_script(&mut ctx, reader, "stdin", false).code();
+    run_exit_handlers(&mut ctx);
+
+    exit_code
+}
+
+pub fn set_positional_args(ctx: &mut Context, name: String, args: Vec<String>) {
+    ctx.set_variable("ARGC", args.len(), false);
+    ctx.set_variable("0", name, false);
+    for (idx, arg) in args.into_iter().enumerate() {
+        ctx.set_variable(&(idx + 1).to_string(), arg, false);
+    }
+}
+
+// Run each line of a script, name is the file shown in errors
+fn run_script<R: BufRead>(ctx: &mut Context, reader: R, name: &str, stop_on_error: bool) -> ExecOutcome {
+    let mut last_output = ExecOutcome::Status(0);
+    let mut buffer = String::new();
+    let mut start_line = 0;
+
+    for (line_num, line) in reader.lines().enumerate() {
+        let line = match line {
+            Ok(line) => line,
+            Err(why) => {
+                eprintln!("Unable to read {}:{}! {}", name, line_num + 1, why);
+                return ExecOutcome::Status(1);
+            },
+        };
+
+        if buffer.is_empty() {
+            start_line = line_num;
+        }
+        buffer.push_str(&line);
+
+        match needs_continuation(&buffer) {
+            Some(Continuation::Escape) => {
+                buffer.pop();
+                continue;
+            },
+            Some(Continuation::Operator) => {
+                buffer.push(' ');
+                continue;
+            },
+            Some(Continuation::Quote) => {
+                buffer.push('\n');
+                continue;
+            },
+            None => {},
+        }
+
+        ctx.cli.command_buffer = buffer;
+        buffer = String::new();
+
+        let output = execute(ctx);
+        if output == ExecOutcome::ParseError {
+            eprintln!("  at {}:{}", name, start_line + 1);
         }
+
+        // Reap finished background jobs, they're kept in the
+        // table so that 'wait' can still get their status
+        #[cfg(unix)]
+        if let Err(why) = ctx.jobs.poll() {
+            eprintln!("Unable to update jobs! {}", why);
+        }
+
+        #[cfg(unix)]
+        let output = match br_executer::run_traps(ctx) {
+            ExecOutcome::Exit(code) => ExecOutcome::Exit(code),
+            _ => output,
+        };
+
+        match output {
+            ExecOutcome::Exit(_) | ExecOutcome::Return(_) => return output,
+            _ => last_output = output,
+        }
+
+        if stop_on_error && !output.is_success() {
+            eprintln!("Non 0 exit code returned while running file!");
+            eprintln!("{}:{}: '{}'", name, start_line + 1, ctx.cli.command_buffer);
+
+            return output;
+        }
+    }
+
+    if !buffer.is_empty() {
+        eprintln!("Unexpected end of input while parsing {}:{}!", name, start_line + 1);
+        return ExecOutcome::ParseError;
     }
+
+    last_output
+}
+
+fn read_file(path: &Path) -> std::io::Result<String> {
+    let mut file = OpenOptions::new().read(true).open(path)?;
+
+    let mut contents = String::new();
+    file.read_to_string(&mut contents)?;
+
+    Ok(contents)
 }
 
 fn write_default_config(file: PathBuf) {
-    let mut config = match OpenOptions::new().write(true).create(true).open(file) {
+    let mut config = match OpenOptions::new().write(true).create(true).truncate(true).open(file) {
         Ok(conf) => conf,
         Err(why) => {
             eprintln!("Unable to create default branshrc.br! {}", why);
@@ -82,6 +220,8 @@ set P_DIR_TRUNC 2
 set P_DIR_CHAR  "…"
 
 set SYN_HIGHLIGHTING true
+
+set BG_OUTPUT true
 "#
     .trim();
 
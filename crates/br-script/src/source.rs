@@ -0,0 +1,203 @@
+use std::{
+    env,
+    path::{Path, PathBuf},
+};
+
+use br_data::{
+    command::{BrBuiltin, CommandIo, ExecOutcome, ExecuteFn, TabCompletionType},
+    context::Context,
+};
+
+use crate::{read_file, run_script};
+
+pub const CMD: BrBuiltin = BrBuiltin {
+    name: "source",
+    tab_completion: TabCompletionType::File(Vec::new()),
+    execute,
+};
+
+// Short name for 'source'
+pub const DOT_CMD: BrBuiltin = BrBuiltin {
+    name: ".",
+    tab_completion: TabCompletionType::File(Vec::new()),
+    execute,
+};
+
+// Stops files that source themselves from recursing forever
+const MAX_DEPTH: usize = 100;
+
+#[allow(non_upper_case_globals)]
+const execute: ExecuteFn = |mut args: Vec<String>, ctx: &mut Context, io: &mut CommandIo| -> ExecOutcome {
+    if args.is_empty() {
+        io.eprintln("Invalid arguments! Expected at least 1, got 0");
+
+        return ExecOutcome::Status(1);
+    }
+
+    let name = args.remove(0);
+    let path = match find_script(&name) {
+        Some(path) => path,
+        None => {
+            io.eprintln(format!("Unable to find script '{}'!", name));
+
+            return ExecOutcome::Status(127);
+        },
+    };
+
+    let script = match read_file(&path) {
+        Ok(script) => script,
+        Err(why) => {
+            io.eprintln(format!("Unable to read script '{}'! {}", path.display(), why));
+
+            return ExecOutcome::Status(1);
+        },
+    };
+
+    if ctx.source_depth >= MAX_DEPTH {
+        io.eprintln(format!("Unable to source '{}'! Too many nested scripts", path.display()));
+
+        return ExecOutcome::Status(1);
+    }
+
+    // Arguments replace the positional variables while the script
+    // runs, otherwise it sees the current ones
+    let saved_args = if args.is_empty() { None } else { Some(swap_positional_args(ctx, Some(args))) };
+    let buffer = ctx.cli.command_buffer.clone();
+
+    ctx.source_depth += 1;
+    let output = run_script(ctx, script.as_bytes(), &path.to_string_lossy(), false);
+    ctx.source_depth -= 1;
+
+    ctx.cli.command_buffer = buffer;
+    if let Some(saved_args) = saved_args {
+        swap_positional_args(ctx, saved_args);
+    }
+
+    match output {
+        ExecOutcome::Return(code) => ExecOutcome::Status(code),
+        output => output,
+    }
+};
+
+// Names without a '/' are searched for on PATH and then in the
+// current directory
+fn find_script(name: &str) -> Option<PathBuf> {
+    if name.contains('/') {
+        return Some(PathBuf::from(name));
+    }
+
+    let paths = env::var_os("PATH").unwrap_or_default();
+    env::split_paths(&paths)
+        .map(|dir| dir.join(name))
+        .chain(std::iter::once(PathBuf::from(name)))
+        .find(|path| Path::new(path).is_file())
+}
+
+// Set the positional variables, returning the previous ones. None
+// leaves ARGC and the positional variables unset
+fn swap_positional_args(ctx: &mut Context, args: Option<Vec<String>>) -> Option<Vec<String>> {
+    let old_args = ctx.get_variable_value("ARGC", false).map(|_| {
+        let argc = ctx.get_variable("ARGC", 0, false);
+        (1..=argc)
+            .map(|idx| ctx.get_variable(&idx.to_string(), String::new(), false))
+            .collect::<Vec<String>>()
+    });
+
+    for idx in 1..=old_args.as_ref().map_or(0, Vec::len) {
+        ctx.unset_variable(&idx.to_string(), false);
+    }
+
+    let args = match args {
+        Some(args) => args,
+        None => {
+            ctx.unset_variable("ARGC", false);
+
+            return old_args;
+        },
+    };
+
+    ctx.set_variable("ARGC", args.len(), false);
+    for (idx, arg) in args.into_iter().enumerate() {
+        ctx.set_variable(&(idx + 1).to_string(), arg, false);
+    }
+
+    old_args
+}
+
+// Make cargo stop complaining about functions used for
+// tests
+#[allow(unused_imports, dead_code)]
+mod test {
+    use std::{env, fs, process};
+
+    use br_data::{
+        command::{CommandIo, ExecOutcome},
+        context::Context,
+        universal::UniversalStore,
+    };
+
+    use super::{execute, swap_positional_args};
+    use crate::load_builtins;
+
+    // Output of the script goes to the shell's stdout, so only
+    // errors end up in the builtin's io
+    #[test]
+    fn run_through_io() {
+        let dir = env::temp_dir().join(format!("bransh-source-{}", process::id()));
+        fs::create_dir_all(&dir).unwrap();
+
+        let script = dir.join("script.br");
+        fs::write(&script, "set FIRST $1\nset SECOND \"two\nlines\"\n").unwrap();
+
+        let mut ctx = Context {
+            universal: UniversalStore::default(),
+            ..Context::default()
+        };
+        load_builtins(&mut ctx);
+
+        let stdout = dir.join("stdout.txt");
+        let stderr = dir.join("stderr.txt");
+        let mut io = CommandIo {
+            stdout: Box::new(fs::File::create(&stdout).unwrap()),
+            stderr: Box::new(fs::File::create(&stderr).unwrap()),
+            ..CommandIo::default()
+        };
+
+        let args = vec![script.to_string_lossy().to_string(), String::from("one")];
+        assert_eq!(execute(args, &mut ctx, &mut io), ExecOutcome::Status(0));
+        assert_eq!(ctx.get_variable("FIRST", String::new(), false), "one");
+        assert_eq!(ctx.get_variable("SECOND", String::new(), false), "two\nlines");
+        assert_eq!(ctx.get_variable_value("ARGC", false), None);
+
+        let missing = String::from("bransh-missing-script.br");
+        assert_eq!(execute(vec![missing], &mut ctx, &mut io), ExecOutcome::Status(127));
+
+        drop(io);
+        assert_eq!(fs::read_to_string(&stdout).unwrap(), "");
+        assert!(fs::read_to_string(&stderr).unwrap().starts_with("Unable to find script"));
+
+        let _ = fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn positional_args() {
+        let mut ctx = Context::default();
+
+        let saved = swap_positional_args(&mut ctx, Some(vec![String::from("a"), String::from("b")]));
+        assert_eq!(saved, None);
+        assert_eq!(ctx.get_variable("ARGC", 0, false), 2);
+        assert_eq!(ctx.get_variable("2", String::new(), false), "b");
+
+        let saved = swap_positional_args(&mut ctx, Some(vec![String::from("c")]));
+        assert_eq!(saved, Some(vec![String::from("a"), String::from("b")]));
+        assert_eq!(ctx.get_variable_value("2", false), None);
+
+        swap_positional_args(&mut ctx, saved);
+        assert_eq!(ctx.get_variable("ARGC", 0, false), 2);
+        assert_eq!(ctx.get_variable("1", String::new(), false), "a");
+
+        swap_positional_args(&mut ctx, None);
+        assert_eq!(ctx.get_variable_value("ARGC", false), None);
+        assert_eq!(ctx.get_variable_value("1", false), None);
+    }
+}
@@ -0,0 +1,43 @@
+// Sourced scripts write to the shell's stdout, which the builtin
+// points at its redirect or pipe. This replaces the process' fd 1
+// so it's the only test in this binary, anything printed by other
+// tests running at the same time would end up in the output
+
+use std::{env, fs, process};
+
+use br_data::{command::ExecOutcome, context::Context, universal::UniversalStore};
+use br_executer::execute;
+use br_script::load_builtins;
+
+fn run(ctx: &mut Context, command: String) -> ExecOutcome {
+    ctx.cli.command_buffer = command;
+    execute(ctx)
+}
+
+#[test]
+fn redirect_and_pipe() {
+    let dir = env::temp_dir().join(format!("bransh-source-output-{}", process::id()));
+    fs::create_dir_all(&dir).unwrap();
+
+    let script = dir.join("script.br");
+    fs::write(&script, "echo builtin\nsh -c \"echo external\"\n").unwrap();
+
+    // Keep the test away from the user's universal variables
+    let mut ctx = Context {
+        universal: UniversalStore::default(),
+        ..Context::default()
+    };
+    load_builtins(&mut ctx);
+
+    let redirected = dir.join("redirected.txt");
+    let output = run(&mut ctx, format!("source {} > {}", script.display(), redirected.display()));
+    assert_eq!(output, ExecOutcome::Status(0));
+    assert_eq!(fs::read_to_string(&redirected).unwrap(), "builtin\nexternal\n");
+
+    let piped = dir.join("piped.txt");
+    let output = run(&mut ctx, format!(". {} | cat > {}", script.display(), piped.display()));
+    assert_eq!(output, ExecOutcome::Status(0));
+    assert_eq!(fs::read_to_string(&piped).unwrap(), "builtin\nexternal\n");
+
+    let _ = fs::remove_dir_all(&dir);
+}
@@ -1,3 +1,5 @@
+use std::process;
+
 fn main() {
     let opts = br_data::options::Options::parse();
 
@@ -7,11 +9,30 @@ fn main() {
     }
 
     if let Some(command) = opts.command {
-        br_executer::execute_once(command);
-        return;
+        let exit_code = br_script::execute_once(command);
+        process::exit(exit_code);
+    }
+
+    if opts.stdin {
+        let exit_code = br_script::execute_stdin(opts.args);
+        process::exit(exit_code);
+    }
+
+    if let Some((script, args)) = opts.args.split_first() {
+        let exit_code = br_script::execute_file(script.clone(), args.to_vec());
+        process::exit(exit_code);
+    }
+
+    if !opts.interactive && !br_cli::stdin_is_tty() {
+        let exit_code = br_script::execute_stdin(opts.args);
+        process::exit(exit_code);
     }
 
-    if let Err(why) = br_cli::run_term(opts) {
-        eprintln!("Error occured while running terminal! {}", why);
+    match br_cli::run_term(opts) {
+        Ok(exit_code) => process::exit(exit_code),
+        Err(why) => {
+            eprintln!("Error occured while running terminal! {}", why);
+            process::exit(1);
+        },
     }
 }